@@ -1,9 +1,65 @@
+use crate::config::{Identities, ScoringConfig};
+use crate::dormancy;
+use crate::metadata;
+use crate::ownership;
 use crate::registry::{AvailableSignal, Registry, browse_url, check_url};
+use crate::score;
 use crate::types::*;
 use reqwest::Client;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::Semaphore;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// What a check run is doing, sent to `CheckOptions::progress` as it
+/// happens.
+#[derive(Debug, Clone)]
+pub enum Progress {
+    /// `checks` more name × registry lookups have been queued.
+    Queued { checks: usize },
+    /// A lookup got a connection slot and is in flight.
+    Started {
+        name: String,
+        registry_id: &'static str,
+    },
+    /// A lookup finished.
+    Finished {
+        name: String,
+        result: Box<PackageResult>,
+    },
+}
+
+/// Knobs for a check run beyond the names and registries themselves.
+#[derive(Debug, Clone, Default)]
+pub struct CheckOptions {
+    /// Fetch registry metadata (`TakenDetails`) for names that are taken, and
+    /// classify them as active or dormant.
+    pub details: bool,
+    /// With details, fetch only what the registry lookup and its owner
+    /// endpoint return: no download counts, no GitHub archived check, and
+    /// so no dormancy. For scans that only show who holds a name.
+    pub owners_only: bool,
+    /// Our own owner names; matching taken names become `OwnedByYou`.
+    /// Setting these implies fetching details.
+    pub identities: Option<Identities>,
+    /// Registry weights and required registries for `CheckResult::score`.
+    pub scoring: ScoringConfig,
+    /// Receives a `Progress` event as each lookup is queued, starts and
+    /// finishes. Send errors (a dropped receiver) are ignored.
+    pub progress: Option<UnboundedSender<Progress>>,
+}
+
+impl CheckOptions {
+    fn wants_details(&self) -> bool {
+        self.details || self.identities.is_some()
+    }
+
+    fn report(&self, event: Progress) {
+        if let Some(ref progress) = self.progress {
+            let _ = progress.send(event);
+        }
+    }
+}
 
 fn build_client() -> Client {
     Client::builder()
@@ -18,8 +74,27 @@ async fn check_registry(
     registry: &'static Registry,
     name: &str,
     semaphore: &Semaphore,
+    options: &CheckOptions,
 ) -> PackageResult {
     let _permit = semaphore.acquire().await.unwrap();
+    options.report(Progress::Started {
+        name: name.to_string(),
+        registry_id: registry.id,
+    });
+    let result = query_registry(client, registry, name, options).await;
+    options.report(Progress::Finished {
+        name: name.to_string(),
+        result: Box::new(result.clone()),
+    });
+    result
+}
+
+async fn query_registry(
+    client: &Client,
+    registry: &'static Registry,
+    name: &str,
+    options: &CheckOptions,
+) -> PackageResult {
     let start = Instant::now();
     let url = check_url(registry, name);
 
@@ -33,15 +108,40 @@ async fn check_registry(
             let status = response.status();
             let body = response.text().await.ok();
 
-            let available = dispatch_signal(registry.signal, status, body.as_deref());
+            let mut available = dispatch_signal(registry.signal, status, body.as_deref());
+
+            let details = if options.wants_details() && available == Availability::Taken {
+                metadata::fetch_details(
+                    client,
+                    registry,
+                    name,
+                    body.as_deref(),
+                    options.owners_only,
+                )
+                .await
+            } else {
+                None
+            };
+            if let (Some(identities), Some(d)) = (&options.identities, &details)
+                && identities.owns(registry.id, &d.owners, d.repository.as_deref())
+            {
+                available = Availability::OwnedByYou;
+            }
+            let dormancy = details
+                .as_ref()
+                .filter(|_| available == Availability::Taken && !options.owners_only)
+                .map(|d| dormancy::classify(registry.id, d, chrono::Utc::now()));
 
             PackageResult {
                 registry_id: registry.id.to_string(),
                 registry_name: registry.name.to_string(),
                 available,
                 browse_url: Some(browse_url(registry, name)),
+                http_status: Some(status.as_u16()),
                 elapsed_ms: start.elapsed().as_millis() as u64,
                 error: None,
+                details,
+                dormancy,
             }
         }
         Err(e) => PackageResult {
@@ -49,8 +149,11 @@ async fn check_registry(
             registry_name: registry.name.to_string(),
             available: Availability::Unknown,
             browse_url: Some(browse_url(registry, name)),
+            http_status: None,
             elapsed_ms: start.elapsed().as_millis() as u64,
             error: Some(e.to_string()),
+            details: None,
+            dormancy: None,
         },
     }
 }
@@ -143,6 +246,7 @@ async fn check_package_inner(
     registries: &[&'static Registry],
     client: &Client,
     semaphore: &Arc<Semaphore>,
+    options: &Arc<CheckOptions>,
 ) -> CheckResult {
     let start = Instant::now();
     let name = name.trim().to_string();
@@ -152,8 +256,9 @@ async fn check_package_inner(
         let client = client.clone();
         let sem = Arc::clone(semaphore);
         let name = name.clone();
+        let options = Arc::clone(options);
         handles.push(tokio::spawn(async move {
-            check_registry(&client, registry, &name, &sem).await
+            check_registry(&client, registry, &name, &sem, &options).await
         }));
     }
 
@@ -164,51 +269,103 @@ async fn check_package_inner(
         }
     }
 
+    assemble(&name, results, options, start.elapsed().as_millis() as u64)
+}
+
+/// Build a name's `CheckResult` (summary, owners, score) from its
+/// per-registry results, however they were gathered.
+pub fn assemble(
+    name: &str,
+    results: Vec<PackageResult>,
+    options: &CheckOptions,
+    elapsed_ms: u64,
+) -> CheckResult {
+    let count = |status: Availability| results.iter().filter(|r| r.available == status).count();
+    let available = count(Availability::Available);
+    let owned_by_you = count(Availability::OwnedByYou);
     let summary = Summary {
-        available: results
-            .iter()
-            .filter(|r| r.available == Availability::Available)
-            .count(),
-        taken: results
-            .iter()
-            .filter(|r| r.available == Availability::Taken)
-            .count(),
-        unknown: results
-            .iter()
-            .filter(|r| r.available == Availability::Unknown)
-            .count(),
+        available,
+        taken: count(Availability::Taken),
+        unknown: count(Availability::Unknown),
+        owned_by_you,
         total: results.len(),
+        clear: available + owned_by_you == results.len(),
     };
 
+    let owner_groups = ownership::group_by_owner(&results);
+    let dominant_owner = ownership::dominant_owner(&owner_groups);
+    let score = score::score(&results, &options.scoring);
+
     CheckResult {
-        name,
+        name: name.to_string(),
+        variant_of: None,
         summary,
         results,
-        elapsed_ms: start.elapsed().as_millis() as u64,
+        owner_groups,
+        dominant_owner,
+        score,
+        elapsed_ms,
     }
 }
 
 pub async fn check_package(name: &str, registries: &[&'static Registry]) -> CheckResult {
+    check_package_with_options(name, registries, &CheckOptions::default()).await
+}
+
+pub async fn check_package_with_options(
+    name: &str,
+    registries: &[&'static Registry],
+    options: &CheckOptions,
+) -> CheckResult {
     let client = build_client();
     let semaphore = Arc::new(Semaphore::new(20));
-    check_package_inner(name, registries, &client, &semaphore).await
+    let options = Arc::new(options.clone());
+    options.report(Progress::Queued {
+        checks: registries.len(),
+    });
+    check_package_inner(name, registries, &client, &semaphore, &options).await
 }
 
 pub async fn check_packages(
     names: &[String],
     registries: &[&'static Registry],
+) -> Vec<CheckResult> {
+    check_packages_with_options(names, registries, &CheckOptions::default()).await
+}
+
+pub async fn check_packages_with_options(
+    names: &[String],
+    registries: &[&'static Registry],
+    options: &CheckOptions,
+) -> Vec<CheckResult> {
+    let plan: Vec<(String, Vec<&'static Registry>)> = names
+        .iter()
+        .map(|name| (name.clone(), registries.to_vec()))
+        .collect();
+    check_planned(&plan, options).await
+}
+
+/// Check each name against its own list of registries.
+pub async fn check_planned(
+    plan: &[(String, Vec<&'static Registry>)],
+    options: &CheckOptions,
 ) -> Vec<CheckResult> {
     let client = build_client();
     let semaphore = Arc::new(Semaphore::new(20));
+    let options = Arc::new(options.clone());
+    options.report(Progress::Queued {
+        checks: plan.iter().map(|(_, registries)| registries.len()).sum(),
+    });
 
     let mut handles = Vec::new();
-    for name in names {
+    for (name, registries) in plan {
         let name = name.clone();
-        let registries = registries.to_vec();
+        let registries = registries.clone();
         let client = client.clone();
         let sem = Arc::clone(&semaphore);
+        let options = Arc::clone(&options);
         handles.push(tokio::spawn(async move {
-            check_package_inner(&name, &registries, &client, &sem).await
+            check_package_inner(&name, &registries, &client, &sem, &options).await
         }));
     }
 
@@ -0,0 +1,194 @@
+use crate::checker::{self, CheckOptions, Progress};
+use crate::registry::Registry;
+use crate::types::{Availability, CheckResult, PackageResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// One line of a checkpoint file: a finished lookup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    /// `fingerprint` of the options the lookup was made with.
+    #[serde(default)]
+    pub options: String,
+    pub result: PackageResult,
+}
+
+/// The options that change what a lookup returns: whether details were
+/// fetched, and which identities count as ours.
+pub fn fingerprint(options: &CheckOptions) -> String {
+    let mut parts = vec![format!("details={}", options.details)];
+    if let Some(ref identities) = options.identities {
+        let mut github = identities.github.clone();
+        github.sort();
+        parts.push(format!("github={}", github.join(",")));
+        let mut registries: Vec<_> = identities.registries.iter().collect();
+        registries.sort();
+        for (id, owners) in registries {
+            let mut owners = owners.clone();
+            owners.sort();
+            parts.push(format!("{}={}", id, owners.join(",")));
+        }
+    }
+    parts.join(";")
+}
+
+/// Lookups recorded by an earlier run. Unknown results aren't reused, so
+/// lookups that failed (timeouts, rate limits) are retried on resume.
+#[derive(Debug, Clone, Default)]
+pub struct Checkpoint {
+    entries: HashMap<(String, String), PackageResult>,
+    /// Lines that couldn't be parsed, e.g. one cut short by a crash.
+    pub skipped: usize,
+    /// Lookups made with different options (see `fingerprint`), which are
+    /// looked up again.
+    pub stale: usize,
+}
+
+impl Checkpoint {
+    /// Read a checkpoint file; a missing file is an empty checkpoint. Later
+    /// lines for the same name and registry replace earlier ones. Only
+    /// lookups made with the same `options` are kept.
+    pub fn load(path: &Path, options: &CheckOptions) -> Result<Checkpoint, String> {
+        let fingerprint = fingerprint(options);
+        let mut checkpoint = Checkpoint::default();
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(checkpoint),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| format!("{}: {}", path.display(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Entry>(&line) {
+                Ok(entry) if entry.options != fingerprint => checkpoint.stale += 1,
+                Ok(entry) if entry.result.available != Availability::Unknown => {
+                    let key = (entry.name, entry.result.registry_id.clone());
+                    checkpoint.entries.insert(key, entry.result);
+                }
+                Ok(_) => {}
+                Err(_) => checkpoint.skipped += 1,
+            }
+        }
+        Ok(checkpoint)
+    }
+
+    /// Lookups that can be reused.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn get(&self, name: &str, registry_id: &str) -> Option<&PackageResult> {
+        self.entries
+            .get(&(name.to_string(), registry_id.to_string()))
+    }
+
+    /// `plan` without the lookups already recorded.
+    pub fn remaining(
+        &self,
+        plan: &[(String, Vec<&'static Registry>)],
+    ) -> Vec<(String, Vec<&'static Registry>)> {
+        plan.iter()
+            .map(|(name, registries)| {
+                let left = registries
+                    .iter()
+                    .copied()
+                    .filter(|r| self.get(name, r.id).is_none())
+                    .collect();
+                (name.clone(), left)
+            })
+            .collect()
+    }
+
+    /// Add the recorded lookups from `plan` back into `results` (from
+    /// checking `remaining(plan)`), keeping `registries` order.
+    pub fn merge(
+        &self,
+        results: Vec<CheckResult>,
+        plan: &[(String, Vec<&'static Registry>)],
+        registries: &[&'static Registry],
+        options: &CheckOptions,
+    ) -> Vec<CheckResult> {
+        let position = |id: &str| registries.iter().position(|r| r.id == id);
+        results
+            .into_iter()
+            .map(|result| {
+                let planned = plan
+                    .iter()
+                    .find(|(name, _)| *name == result.name)
+                    .map_or(&[][..], |(_, registries)| registries.as_slice());
+                let recorded: Vec<PackageResult> = planned
+                    .iter()
+                    .filter(|r| !result.results.iter().any(|p| p.registry_id == r.id))
+                    .filter_map(|r| self.get(&result.name, r.id).cloned())
+                    .collect();
+                if recorded.is_empty() {
+                    return result;
+                }
+                let mut packages = result.results;
+                packages.extend(recorded);
+                packages.sort_by_key(|p| position(&p.registry_id));
+                let mut merged =
+                    checker::assemble(&result.name, packages, options, result.elapsed_ms);
+                merged.variant_of = result.variant_of;
+                merged
+            })
+            .collect()
+    }
+}
+
+/// Appends each finished lookup to a checkpoint file as it arrives.
+pub struct Writer {
+    file: File,
+    options: String,
+}
+
+impl Writer {
+    /// Open `path` for appending, creating it if needed. A last line cut
+    /// short by a crash is ended so new entries start on their own line.
+    /// Entries are stamped with the `fingerprint` of `options`.
+    pub fn open(path: &Path, options: &CheckOptions) -> std::io::Result<Writer> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        if file.metadata()?.len() > 0 {
+            let mut last = [0u8];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n")?;
+            }
+        }
+        Ok(Writer {
+            file,
+            options: fingerprint(options),
+        })
+    }
+
+    /// Write `event` if it's a finished lookup. Each line is flushed so it
+    /// survives the process being killed.
+    pub fn record(&mut self, event: &Progress) -> std::io::Result<()> {
+        let Progress::Finished { name, result } = event else {
+            return Ok(());
+        };
+        let entry = Entry {
+            name: name.clone(),
+            options: self.options.clone(),
+            result: (**result).clone(),
+        };
+        let line = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()
+    }
+}
@@ -0,0 +1,143 @@
+use crate::ownership;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// User configuration, read from `--config` or
+/// `$XDG_CONFIG_HOME/staked/config.toml` (falling back to `~/.config`).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub identities: Identities,
+    pub audit: AuditConfig,
+    pub scoring: ScoringConfig,
+    pub history: HistoryConfig,
+}
+
+/// Settings for `staked audit`.
+///
+/// ```toml
+/// [audit]
+/// internal = ["@acme/", "acme-"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Name prefixes (npm scopes included) that are always internal.
+    pub internal: Vec<String>,
+}
+
+/// The local database of past checks.
+///
+/// ```toml
+/// [history]
+/// record = true                       # same as passing --record every time
+/// path = "~/notes/staked-history.db"  # default: ~/.local/share/staked/history.db
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub record: bool,
+    pub path: Option<PathBuf>,
+}
+
+impl HistoryConfig {
+    /// `path` with a leading `~/` expanded against `$HOME`. Without `HOME`
+    /// it's left as written.
+    pub fn db_path(&self) -> Option<PathBuf> {
+        let path = self.path.as_ref()?;
+        match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+            (Ok(rest), Some(home)) => Some(PathBuf::from(home).join(rest)),
+            _ => Some(path.clone()),
+        }
+    }
+}
+
+/// How much each registry counts towards a name's score.
+///
+/// ```toml
+/// [scoring]
+/// required = ["npm", "crates"]
+///
+/// [scoring.weights]
+/// docker_hub = 2.0   # overrides the built-in default
+/// cocoapods = 0      # don't care
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    /// Per-registry weights, on top of `score::DEFAULT_WEIGHTS`.
+    pub weights: HashMap<String, f64>,
+    /// Registries a name must be free (or ours) on to be a candidate at all.
+    pub required: Vec<String>,
+}
+
+/// Who "we" are on each registry. Names taken by one of these are reported as
+/// `OwnedByYou` instead of `Taken`.
+///
+/// ```toml
+/// [identities]
+/// npm = ["alice", "acme-bot"]
+/// crates = ["alice", "github:acme:publishers"]
+/// pypi = ["Alice Example"]
+/// github = ["acme"]   # matches repository URLs under github.com/acme/
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Identities {
+    /// GitHub users or orgs; any package whose repository lives under one of
+    /// them counts as ours, on every registry.
+    #[serde(default)]
+    pub github: Vec<String>,
+    /// Owner names keyed by registry ID.
+    #[serde(flatten)]
+    pub registries: HashMap<String, Vec<String>>,
+}
+
+impl Identities {
+    pub fn is_empty(&self) -> bool {
+        self.github.is_empty() && self.registries.values().all(Vec::is_empty)
+    }
+
+    /// Whether a package with these owners and repository belongs to us.
+    pub fn owns(&self, registry_id: &str, owners: &[String], repository: Option<&str>) -> bool {
+        if let Some(ours) = self.registries.get(registry_id)
+            && owners
+                .iter()
+                .any(|owner| ours.iter().any(|id| id.eq_ignore_ascii_case(owner)))
+        {
+            return true;
+        }
+        let Some(project) = repository.and_then(ownership::project_key) else {
+            return false;
+        };
+        self.github.iter().any(|org| {
+            let prefix = format!("github.com/{}/", org.to_lowercase());
+            project.starts_with(&prefix)
+        })
+    }
+}
+
+impl Config {
+    /// Load the config from `path`, or from the default location if it
+    /// exists. A missing default file is not an error.
+    pub fn load(path: Option<&Path>) -> Result<Config, Box<dyn std::error::Error>> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Config::default()),
+            },
+        };
+        let text =
+            std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let config = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(config)
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("staked").join("config.toml"))
+}
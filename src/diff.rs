@@ -0,0 +1,136 @@
+use crate::types::{Availability, Report, SCHEMA_VERSION};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A name whose status on a registry changed between two reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Flip {
+    pub name: String,
+    pub registry_id: String,
+    pub from: Availability,
+    pub to: Availability,
+}
+
+impl Flip {
+    /// Was free (available, or ours) and is now taken by someone.
+    pub fn newly_taken(&self) -> bool {
+        matches!(
+            self.from,
+            Availability::Available | Availability::OwnedByYou
+        ) && self.to == Availability::Taken
+    }
+}
+
+/// A lookup that errored in the new report but not in the old one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewError {
+    pub name: String,
+    pub registry_id: String,
+    pub error: String,
+}
+
+/// What changed from one `--json` report to another.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportDiff {
+    pub flips: Vec<Flip>,
+    pub new_errors: Vec<NewError>,
+    pub added_registries: Vec<String>,
+    pub removed_registries: Vec<String>,
+    pub added_names: Vec<String>,
+    pub removed_names: Vec<String>,
+}
+
+impl ReportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.flips.is_empty()
+            && self.new_errors.is_empty()
+            && self.added_registries.is_empty()
+            && self.removed_registries.is_empty()
+            && self.added_names.is_empty()
+            && self.removed_names.is_empty()
+    }
+
+    pub fn newly_taken(&self) -> impl Iterator<Item = &Flip> {
+        self.flips.iter().filter(|f| f.newly_taken())
+    }
+}
+
+/// Read a `--json` report, refusing ones from a newer schema.
+pub fn load(path: &Path) -> Result<Report, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if value.is_array() {
+        return Err(format!(
+            "{}: a bare results array from before reports had an envelope; re-run with --json",
+            path.display()
+        ));
+    }
+    let report: Report =
+        serde_json::from_value(value).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if report.schema_version > SCHEMA_VERSION {
+        return Err(format!(
+            "{}: schema version {} is newer than this staked understands ({})",
+            path.display(),
+            report.schema_version,
+            SCHEMA_VERSION
+        ));
+    }
+    Ok(report)
+}
+
+/// Compare `old` to `new`. Only names and registries present in both are
+/// checked for flips and new errors; a lookup that newly errored is listed
+/// as an error rather than as a flip to unknown.
+pub fn diff(old: &Report, new: &Report) -> ReportDiff {
+    let mut diff = ReportDiff {
+        added_registries: missing_from(&new.registries, &old.registries),
+        removed_registries: missing_from(&old.registries, &new.registries),
+        ..ReportDiff::default()
+    };
+    let old_names: Vec<String> = old.results.iter().map(|r| r.name.clone()).collect();
+    let new_names: Vec<String> = new.results.iter().map(|r| r.name.clone()).collect();
+    diff.added_names = missing_from(&new_names, &old_names);
+    diff.removed_names = missing_from(&old_names, &new_names);
+
+    let old_by_name: HashMap<&str, _> = old.results.iter().map(|r| (r.name.as_str(), r)).collect();
+    for result in &new.results {
+        let Some(before) = old_by_name.get(result.name.as_str()) else {
+            continue;
+        };
+        for pkg in &result.results {
+            let Some(was) = before
+                .results
+                .iter()
+                .find(|p| p.registry_id == pkg.registry_id)
+            else {
+                continue;
+            };
+            let new_error = pkg.error.as_ref().filter(|_| was.error.is_none());
+            if let Some(error) = new_error {
+                diff.new_errors.push(NewError {
+                    name: result.name.clone(),
+                    registry_id: pkg.registry_id.clone(),
+                    error: error.clone(),
+                });
+            } else if was.available != pkg.available {
+                diff.flips.push(Flip {
+                    name: result.name.clone(),
+                    registry_id: pkg.registry_id.clone(),
+                    from: was.available.clone(),
+                    to: pkg.available.clone(),
+                });
+            }
+        }
+    }
+    diff
+}
+
+/// Items of `list` that aren't in `other`, in `list` order.
+fn missing_from(list: &[String], other: &[String]) -> Vec<String> {
+    list.iter()
+        .filter(|s| !other.contains(s))
+        .cloned()
+        .collect()
+}
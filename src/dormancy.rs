@@ -0,0 +1,121 @@
+use crate::types::{Activity, Dormancy, TakenDetails, TransferPolicy};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+/// Score at or above which a taken name is classified as dormant.
+pub const DORMANT_THRESHOLD: u8 = 50;
+
+/// Classify a taken name from its registry metadata. Each signal adds to a
+/// 0-100 score: time since the last release, how few releases there were,
+/// low download counts, and an archived or missing repository.
+pub fn classify(registry_id: &str, details: &TakenDetails, now: DateTime<Utc>) -> Dormancy {
+    let mut score: u32 = 0;
+    let mut reasons = Vec::new();
+
+    if let Some(published) = details.last_published.as_deref().and_then(parse_timestamp) {
+        let years = (now - published).num_days() as f64 / 365.25;
+        let points = if years >= 5.0 {
+            45
+        } else if years >= 3.0 {
+            35
+        } else if years >= 2.0 {
+            20
+        } else if years >= 1.0 {
+            10
+        } else {
+            0
+        };
+        if points > 0 {
+            score += points;
+            reasons.push(format!(
+                "last release {:.1} years ago ({})",
+                years,
+                published.format("%Y-%m-%d")
+            ));
+        }
+    }
+
+    match details.release_count {
+        Some(1) => {
+            score += 20;
+            reasons.push("only one release".to_string());
+        }
+        Some(n @ 2..=3) => {
+            score += 10;
+            reasons.push(format!("only {n} releases"));
+        }
+        _ => {}
+    }
+
+    if let Some(ref downloads) = details.downloads {
+        let low = match downloads.period.as_str() {
+            "total" => downloads.count < 1_000,
+            "365d" => downloads.count < 100,
+            _ => downloads.count < 10,
+        };
+        if low {
+            score += 15;
+            reasons.push(format!(
+                "{} downloads ({})",
+                downloads.count, downloads.period
+            ));
+        }
+    }
+
+    match (details.repository_archived, &details.repository) {
+        (Some(true), _) => {
+            score += 20;
+            reasons.push("repository is archived".to_string());
+        }
+        (_, None) => {
+            score += 5;
+            reasons.push("no repository listed".to_string());
+        }
+        _ => {}
+    }
+
+    let score = score.min(100) as u8;
+    Dormancy {
+        status: if score >= DORMANT_THRESHOLD {
+            Activity::Dormant
+        } else {
+            Activity::Active
+        },
+        score,
+        reasons,
+        transfer_policy: transfer_policy(registry_id),
+    }
+}
+
+/// The registry's documented process for claiming an abandoned name.
+pub fn transfer_policy(registry_id: &str) -> Option<TransferPolicy> {
+    let (name, url) = match registry_id {
+        "pypi" => ("PEP 541", "https://peps.python.org/pep-0541/"),
+        "npm" => (
+            "npm dispute policy",
+            "https://docs.npmjs.com/policies/disputes",
+        ),
+        "crates" => ("crates.io policies", "https://crates.io/policies"),
+        _ => return None,
+    };
+    Some(TransferPolicy {
+        name: name.to_string(),
+        url: url.to_string(),
+    })
+}
+
+/// Parse the timestamp formats registries use: RFC 3339, `YYYY-MM-DD HH:MM:SS`
+/// with an optional zone suffix, or a bare date.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Some(prefix) = value.get(..19)
+        && let Ok(dt) =
+            NaiveDateTime::parse_from_str(&prefix.replace('T', " "), "%Y-%m-%d %H:%M:%S")
+    {
+        return Some(dt.and_utc());
+    }
+    let date = NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
@@ -0,0 +1,226 @@
+use crate::output::table::status_key;
+use crate::types::{CheckResult, PackageResult};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Bumped when the table layout changes; stored in `PRAGMA user_version`.
+const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS checks (
+    id          INTEGER PRIMARY KEY,
+    checked_at  TEXT NOT NULL,
+    name        TEXT NOT NULL,
+    registry_id TEXT NOT NULL,
+    status      TEXT NOT NULL,
+    http_status INTEGER,
+    elapsed_ms  INTEGER NOT NULL,
+    error       TEXT
+);
+CREATE INDEX IF NOT EXISTS checks_by_name ON checks (name, registry_id, checked_at);
+CREATE INDEX IF NOT EXISTS checks_by_time ON checks (checked_at);
+";
+
+/// `$XDG_DATA_HOME/staked/history.db` (falling back to `~/.local/share`).
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(base.join("staked").join("history.db"))
+}
+
+/// A run of consecutive checks of one name on one registry with the same
+/// status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Span {
+    pub registry_id: String,
+    pub status: String,
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+    pub checks: u64,
+}
+
+/// How one registry behaved over one period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryStats {
+    pub registry_id: String,
+    /// Start of the period, e.g. `2026-10-12` for a week.
+    pub period: String,
+    pub checks: u64,
+    /// Share of checks that came back unknown, 0.0 to 1.0.
+    pub error_rate: f64,
+    pub mean_ms: f64,
+    pub max_ms: u64,
+}
+
+/// How `stats` buckets checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Period {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl Period {
+    /// SQLite expression for the start of the period `checked_at` falls in.
+    fn bucket(self) -> &'static str {
+        match self {
+            Period::Day => "date(checked_at)",
+            Period::Week => "date(checked_at, 'weekday 0', '-6 days')",
+            Period::Month => "date(checked_at, 'start of month')",
+        }
+    }
+}
+
+/// The local database of past checks.
+pub struct History {
+    conn: Connection,
+}
+
+impl History {
+    /// Open (creating if needed) the database at `path`.
+    pub fn open(path: &Path) -> Result<History, String> {
+        let fail = |e: &dyn std::fmt::Display| format!("{}: {}", path.display(), e);
+        if let Some(dir) = path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(dir).map_err(|e| fail(&e))?;
+        }
+        let conn = Connection::open(path).map_err(|e| fail(&e))?;
+        let version: i32 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|e| fail(&e))?;
+        if version > SCHEMA_VERSION {
+            return Err(fail(&format!(
+                "history schema {} is newer than this staked understands ({})",
+                version, SCHEMA_VERSION
+            )));
+        }
+        conn.execute_batch(SCHEMA).map_err(|e| fail(&e))?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(|e| fail(&e))?;
+        Ok(History { conn })
+    }
+
+    /// Record every per-registry result of a run, all stamped `at`.
+    pub fn record(&mut self, results: &[CheckResult], at: DateTime<Utc>) -> Result<(), String> {
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        for result in results {
+            for pkg in &result.results {
+                insert(&tx, &result.name, pkg, at)?;
+            }
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    /// Record a single lookup of `name`, stamped `at`.
+    pub fn record_lookup(
+        &mut self,
+        name: &str,
+        pkg: &PackageResult,
+        at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        insert(&self.conn, name, pkg, at)
+    }
+
+    /// Every check of `name`, per registry, collapsed into runs of the same
+    /// status, oldest first.
+    pub fn timeline(&self, name: &str) -> Result<Vec<Span>, String> {
+        let mut query = self
+            .conn
+            .prepare(
+                "SELECT registry_id, status, checked_at FROM checks
+                 WHERE name = ?1 ORDER BY registry_id, checked_at",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = query
+            .query_map([name], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut spans: Vec<Span> = Vec::new();
+        for row in rows {
+            let (registry_id, status, checked_at) = row.map_err(|e| e.to_string())?;
+            let at = DateTime::parse_from_rfc3339(&checked_at)
+                .map_err(|e| format!("bad timestamp '{}': {}", checked_at, e))?
+                .with_timezone(&Utc);
+            match spans.last_mut() {
+                Some(span) if span.registry_id == registry_id && span.status == status => {
+                    span.last = at;
+                    span.checks += 1;
+                }
+                _ => spans.push(Span {
+                    registry_id,
+                    status,
+                    first: at,
+                    last: at,
+                    checks: 1,
+                }),
+            }
+        }
+        Ok(spans)
+    }
+
+    /// Latency and error rate per registry and period, oldest period first.
+    pub fn stats(&self, period: Period) -> Result<Vec<RegistryStats>, String> {
+        let sql = format!(
+            "SELECT registry_id, {bucket} AS period, COUNT(*),
+                    SUM(status = 'unknown'), AVG(elapsed_ms), MAX(elapsed_ms)
+             FROM checks GROUP BY registry_id, period ORDER BY registry_id, period",
+            bucket = period.bucket()
+        );
+        let mut query = self.conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let rows = query
+            .query_map([], |row| {
+                let checks: i64 = row.get(2)?;
+                let errors: i64 = row.get(3)?;
+                Ok(RegistryStats {
+                    registry_id: row.get(0)?,
+                    period: row.get(1)?,
+                    checks: checks as u64,
+                    error_rate: errors as f64 / checks.max(1) as f64,
+                    mean_ms: row.get(4)?,
+                    max_ms: row.get::<_, i64>(5)? as u64,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+    }
+}
+
+fn insert(
+    conn: &Connection,
+    name: &str,
+    pkg: &PackageResult,
+    at: DateTime<Utc>,
+) -> Result<(), String> {
+    let mut insert = conn
+        .prepare_cached(
+            "INSERT INTO checks
+             (checked_at, name, registry_id, status, http_status, elapsed_ms, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .map_err(|e| e.to_string())?;
+    insert
+        .execute(params![
+            at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            name,
+            pkg.registry_id,
+            status_key(&pkg.available),
+            pkg.http_status,
+            pkg.elapsed_ms as i64,
+            pkg.error,
+        ])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,413 @@
+use crate::checker::{self, CheckOptions};
+use crate::registry::{self, Registry};
+use crate::types::CheckResult;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// How a `--input` file lists names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum InputFormat {
+    /// Guess from the file extension, then from the first line
+    #[default]
+    Auto,
+    /// One name per line; blank lines and `#` comments are skipped
+    Lines,
+    /// CSV with a header row; names come from one column
+    Csv,
+    /// One JSON string, or object with a "name" field, per line
+    Ndjson,
+}
+
+/// A line of input that couldn't be read as a name.
+#[derive(Debug, Clone)]
+pub struct InputError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Names read from input, plus the lines that were skipped and why.
+#[derive(Debug, Clone, Default)]
+pub struct Parsed {
+    pub names: Vec<String>,
+    pub errors: Vec<InputError>,
+}
+
+/// Read names from `text`. `path` helps `Auto` guess the format; `column`
+/// is the CSV header to take names from. Bad lines are collected in
+/// `errors`; only a problem with the whole input (a CSV header without
+/// `column`) is an `Err`.
+pub fn parse(
+    text: &str,
+    format: InputFormat,
+    column: &str,
+    path: Option<&Path>,
+) -> Result<Parsed, String> {
+    let format = match format {
+        InputFormat::Auto => guess_format(text, path),
+        other => other,
+    };
+    let mut parsed = Parsed::default();
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    let column_index = if format == InputFormat::Csv {
+        let Some((_, header)) = lines.next() else {
+            return Ok(parsed);
+        };
+        let header = csv_fields(header).map_err(|e| format!("CSV header: {}", e))?;
+        let index = header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(column))
+            .ok_or_else(|| {
+                format!(
+                    "no '{}' column in the CSV header (columns: {})",
+                    column,
+                    header.join(", ")
+                )
+            })?;
+        Some(index)
+    } else {
+        None
+    };
+
+    for (line_no, line) in lines {
+        let name = match format {
+            InputFormat::Csv => csv_fields(line).and_then(|fields| {
+                let index = column_index.expect("set for CSV");
+                fields
+                    .get(index)
+                    .cloned()
+                    .ok_or_else(|| format!("no '{}' field", column))
+            }),
+            InputFormat::Ndjson => ndjson_name(line),
+            _ if line.starts_with('#') => continue,
+            _ => Ok(line.to_string()),
+        };
+        match name.and_then(|name| check_name(name.trim())) {
+            Ok(name) => parsed.names.push(name),
+            Err(message) => parsed.errors.push(InputError {
+                line: line_no,
+                message,
+            }),
+        }
+    }
+    Ok(parsed)
+}
+
+fn guess_format(text: &str, path: Option<&Path>) -> InputFormat {
+    let extension = path
+        .and_then(|p| p.extension())
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("csv") => return InputFormat::Csv,
+        Some("ndjson" | "jsonl") => return InputFormat::Ndjson,
+        _ => {}
+    }
+    let first = text.lines().map(str::trim).find(|line| !line.is_empty());
+    match first {
+        Some(line) if line.starts_with('{') || line.starts_with('"') => InputFormat::Ndjson,
+        _ => InputFormat::Lines,
+    }
+}
+
+fn check_name(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        return Err("empty name".to_string());
+    }
+    if name.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("'{}' contains whitespace", name));
+    }
+    Ok(name.to_string())
+}
+
+fn ndjson_name(line: &str) -> Result<String, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(line).map_err(|e| format!("invalid JSON: {}", e))?;
+    match value {
+        serde_json::Value::String(name) => Ok(name),
+        serde_json::Value::Object(ref object) => match object.get("name") {
+            Some(serde_json::Value::String(name)) => Ok(name.clone()),
+            _ => Err("object has no string \"name\" field".to_string()),
+        },
+        _ => Err("expected a string or an object with a \"name\" field".to_string()),
+    }
+}
+
+/// Split one CSV record (RFC 4180 quoting, no embedded newlines).
+fn csv_fields(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Names to check with repeat lookups removed: exact duplicates are
+/// dropped, and a lookup whose name normalizes (per registry) to one
+/// already planned is shared with that name instead of repeated, so
+/// `foo_bar` and `foo-bar` are looked up on PyPI once.
+#[derive(Clone)]
+pub struct Dedup {
+    plan: Vec<(String, Vec<&'static Registry>)>,
+    /// (name index, registry, index of the name whose lookup it reuses)
+    shared: Vec<(usize, &'static Registry, usize)>,
+    registries: Vec<&'static Registry>,
+}
+
+impl Dedup {
+    pub fn new(names: &[String], registries: &[&'static Registry]) -> Dedup {
+        let mut seen_names = HashSet::new();
+        let mut seen: HashMap<(&str, String), usize> = HashMap::new();
+        let mut plan = Vec::new();
+        let mut shared = Vec::new();
+        for name in names {
+            let name = name.trim();
+            if name.is_empty() || !seen_names.insert(name.to_string()) {
+                continue;
+            }
+            let index = plan.len();
+            let mut lookups = Vec::new();
+            for &registry in registries {
+                let key = (registry.id, registry::normalize(registry, name));
+                match seen.get(&key) {
+                    Some(&first) => shared.push((index, registry, first)),
+                    None => {
+                        seen.insert(key, index);
+                        lookups.push(registry);
+                    }
+                }
+            }
+            plan.push((name.to_string(), lookups));
+        }
+        Dedup {
+            plan,
+            shared,
+            registries: registries.to_vec(),
+        }
+    }
+
+    /// Each name and the registries it still needs looking up on.
+    pub fn plan(&self) -> &[(String, Vec<&'static Registry>)] {
+        &self.plan
+    }
+
+    /// Fill shared lookups back into the results of `check_planned(plan())`
+    /// and return one result per name, in input order, each with every
+    /// registry.
+    pub fn merge(&self, results: Vec<CheckResult>, options: &CheckOptions) -> Vec<CheckResult> {
+        let mut by_name: HashMap<String, CheckResult> =
+            results.into_iter().map(|r| (r.name.clone(), r)).collect();
+        let mut filled = Vec::new();
+        for &(index, registry, first) in &self.shared {
+            let name = &self.plan[index].0;
+            let found = by_name.get(&self.plan[first].0).and_then(|r| {
+                r.results
+                    .iter()
+                    .find(|p| p.registry_id == registry.id)
+                    .cloned()
+            });
+            if let Some(mut pkg) = found {
+                pkg.browse_url = Some(registry::browse_url(registry, name));
+                filled.push((name.clone(), pkg));
+            }
+        }
+        for (name, pkg) in filled {
+            if let Some(result) = by_name.get_mut(&name)
+                && !result
+                    .results
+                    .iter()
+                    .any(|p| p.registry_id == pkg.registry_id)
+            {
+                result.results.push(pkg);
+            }
+        }
+
+        let touched: HashSet<usize> = self.shared.iter().map(|&(index, _, _)| index).collect();
+        let position = |id: &str| self.registries.iter().position(|r| r.id == id);
+        self.plan
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (name, _))| Some((index, by_name.remove(name)?)))
+            .map(|(index, result)| {
+                if !touched.contains(&index) {
+                    return result;
+                }
+                let mut packages = result.results;
+                packages.sort_by_key(|p| position(&p.registry_id));
+                let mut merged =
+                    checker::assemble(&result.name, packages, options, result.elapsed_ms);
+                merged.variant_of = result.variant_of;
+                merged
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Availability, PackageResult};
+
+    fn registries(ids: &[&str]) -> Vec<&'static Registry> {
+        let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        registry::registries_by_ids(&ids)
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn lookup(registry: &Registry, available: Availability) -> PackageResult {
+        PackageResult {
+            registry_id: registry.id.to_string(),
+            registry_name: registry.name.to_string(),
+            available,
+            browse_url: None,
+            http_status: None,
+            elapsed_ms: 1,
+            error: None,
+            details: None,
+            dormancy: None,
+        }
+    }
+
+    #[test]
+    fn dedup_shares_lookups_that_normalize_alike() {
+        let registries = registries(&["npm", "pypi"]);
+        let dedup = Dedup::new(&names(&["foo-bar", "foo_bar", "foo-bar"]), &registries);
+        let plan: Vec<(&str, Vec<&str>)> = dedup
+            .plan()
+            .iter()
+            .map(|(name, rs)| (name.as_str(), rs.iter().map(|r| r.id).collect()))
+            .collect();
+        // npm keeps `_` distinct; PyPI folds it, so foo_bar reuses foo-bar's lookup.
+        assert_eq!(
+            plan,
+            vec![("foo-bar", vec!["npm", "pypi"]), ("foo_bar", vec!["npm"])]
+        );
+    }
+
+    #[test]
+    fn dedup_merge_fills_shared_lookups_in_registry_order() {
+        let registries = registries(&["npm", "pypi"]);
+        let (npm, pypi) = (registries[0], registries[1]);
+        let dedup = Dedup::new(&names(&["foo-bar", "foo_bar"]), &registries);
+        let options = CheckOptions::default();
+        let checked = vec![
+            checker::assemble(
+                "foo-bar",
+                vec![
+                    lookup(npm, Availability::Available),
+                    lookup(pypi, Availability::Taken),
+                ],
+                &options,
+                1,
+            ),
+            checker::assemble(
+                "foo_bar",
+                vec![lookup(npm, Availability::Available)],
+                &options,
+                1,
+            ),
+        ];
+        let merged = dedup.merge(checked, &options);
+
+        assert_eq!(merged.len(), 2);
+        let shared = &merged[1];
+        assert_eq!(shared.name, "foo_bar");
+        let ids: Vec<&str> = shared
+            .results
+            .iter()
+            .map(|p| p.registry_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["npm", "pypi"]);
+        assert_eq!(shared.results[1].available, Availability::Taken);
+        assert_eq!(
+            shared.results[1].browse_url.as_deref(),
+            Some(registry::browse_url(pypi, "foo_bar").as_str())
+        );
+        assert_eq!(shared.summary.taken, 1);
+        assert_eq!(shared.summary.total, 2);
+    }
+
+    #[test]
+    fn csv_fields_handles_quotes() {
+        assert_eq!(csv_fields("a,b,,c").unwrap(), vec!["a", "b", "", "c"]);
+        assert_eq!(
+            csv_fields(r#""foo, bar","say ""hi""",x"#).unwrap(),
+            vec!["foo, bar", r#"say "hi""#, "x"]
+        );
+        assert!(csv_fields(r#""open,field"#).is_err());
+    }
+
+    #[test]
+    fn ndjson_name_reads_strings_and_objects() {
+        assert_eq!(ndjson_name(r#""staked""#).unwrap(), "staked");
+        assert_eq!(
+            ndjson_name(r#"{"name": "staked", "note": 1}"#).unwrap(),
+            "staked"
+        );
+        assert!(ndjson_name(r#"{"title": "staked"}"#).is_err());
+        assert!(ndjson_name("42").is_err());
+        assert!(ndjson_name("{not json").is_err());
+    }
+
+    #[test]
+    fn parse_collects_bad_lines() {
+        let text = "{\"name\": \"one\"}\n\n\"two\"\n[1]\n\"has space\"\n";
+        let parsed = parse(text, InputFormat::Auto, "name", None).unwrap();
+        assert_eq!(parsed.names, vec!["one", "two"]);
+        let lines: Vec<usize> = parsed.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![4, 5]);
+    }
+
+    #[test]
+    fn parse_csv_takes_the_named_column() {
+        let text = "id,Name\n1,alpha\n2,\"beta\"\n3\n";
+        let parsed = parse(text, InputFormat::Csv, "name", None).unwrap();
+        assert_eq!(parsed.names, vec!["alpha", "beta"]);
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line, 4);
+        assert!(parse(text, InputFormat::Csv, "package", None).is_err());
+    }
+
+    #[test]
+    fn parse_lines_skips_comments() {
+        let parsed = parse(
+            "# names\nalpha\n  beta  \n",
+            InputFormat::Lines,
+            "name",
+            None,
+        )
+        .unwrap();
+        assert_eq!(parsed.names, vec!["alpha", "beta"]);
+        assert!(parsed.errors.is_empty());
+    }
+}
@@ -1,3 +1,21 @@
+pub mod audit;
 pub mod checker;
+pub mod checkpoint;
+pub mod config;
+pub mod diff;
+pub mod dormancy;
+pub mod history;
+pub mod input;
+pub mod metadata;
+pub mod mine;
+pub mod output;
+pub mod ownership;
+pub mod policy;
 pub mod registry;
+pub mod score;
+pub mod squat;
+pub mod suggest;
+pub mod thesaurus;
 pub mod types;
+pub mod variants;
+pub mod verify;
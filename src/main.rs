@@ -1,61 +1,1099 @@
-use clap::Parser;
-use staked::checker;
+use clap::{Args, Parser, Subcommand};
+use staked::audit;
+use staked::checker::{self, CheckOptions, Progress};
+use staked::checkpoint::{self, Checkpoint};
+use staked::config::Config;
+use staked::diff;
+use staked::history::{self, History, Period};
+use staked::input::{self, Dedup, InputFormat};
+use staked::mine::{self, Constraints, MineState, Pace};
+use staked::output::progress;
+use staked::output::template::{self, Template};
+use staked::output::terminal::{self, ColorChoice, Style};
+use staked::output::{self, Format, table};
+use staked::policy::{self, FailOn};
 use staked::registry;
-use staked::types::Availability;
+use staked::score;
+use staked::squat;
+use staked::suggest;
+use staked::thesaurus;
+use staked::types::{CheckResult, Report, ReportOptions};
+use staked::variants;
+use staked::verify::{self, Expect, Manifest};
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(
     name = "staked",
     about = "Package registry name availability checker",
-    version
+    version,
+    args_conflicts_with_subcommands = true
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Package names to check
     names: Vec<String>,
 
-    /// Comma-separated registry IDs
-    #[arg(short, long)]
-    registries: Option<String>,
+    /// Also read names from this file ('-' for stdin)
+    #[arg(short, long, value_name = "PATH")]
+    input: Option<PathBuf>,
 
-    /// Filter by language
-    #[arg(short, long)]
-    languages: Option<String>,
+    /// How --input lists names
+    #[arg(long, value_enum, default_value = "auto")]
+    input_format: InputFormat,
 
-    /// Check all 30 registries
-    #[arg(short, long)]
-    all: bool,
+    /// CSV column holding the names
+    #[arg(long, default_value = "name")]
+    column: String,
+
+    /// Append each finished lookup to this JSONL file, and reuse the ones
+    /// already in it, so an interrupted run can be resumed
+    #[arg(long, value_name = "PATH")]
+    checkpoint: Option<PathBuf>,
 
-    /// Output results as JSON
+    #[command(flatten)]
+    select: RegistrySelection,
+
+    /// Output results as JSON (same as --format json)
     #[arg(short, long)]
     json: bool,
 
+    /// Report format
+    #[arg(long, value_enum, default_value = "text")]
+    format: Format,
+
+    /// Row template, rendered once per name and registry, e.g.
+    /// '{name}\t{registry_id}\t{status}\t{url}'
+    #[arg(long, conflicts_with_all = ["format", "json", "template_file"])]
+    template: Option<String>,
+
+    /// Whole-report template file, with {#results}...{/results} and
+    /// {#registries}...{/registries} loops
+    #[arg(long, conflicts_with_all = ["format", "json"])]
+    template_file: Option<PathBuf>,
+
+    /// Write the report to this file instead of stdout (not for text output)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
     /// Show per-registry detail
     #[arg(short, long)]
     verbose: bool,
 
+    /// Fetch metadata for taken names (description, version, owners, downloads)
+    #[arg(short, long)]
+    details: bool,
+
+    /// Also check separator forms and ecosystem affixes of each name
+    #[arg(long)]
+    variants: bool,
+
+    /// Show names side by side: one column per name, one row per registry
+    #[arg(short, long)]
+    matrix: bool,
+
+    /// One line per name: free count, score and what isn't free
+    #[arg(long, conflicts_with_all = ["verbose", "matrix"])]
+    compact: bool,
+
+    /// Colour text output
+    #[arg(long, value_enum, default_value = "auto")]
+    color: ColorChoice,
+
+    /// Exit 1 unless every name is free (or ours) on these comma-separated
+    /// registries; they are checked even if not otherwise selected
+    #[arg(long, value_name = "IDS")]
+    require: Option<String>,
+
+    /// Also fail when any checked registry is taken (exit 1) or unknown (exit 2)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    fail_on: Vec<FailOn>,
+
+    /// Record this run's lookups in the history database
+    #[arg(long, global = true)]
+    record: bool,
+
+    /// Config file (default: ~/.config/staked/config.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// History database (default: ~/.local/share/staked/history.db)
+    #[arg(long, global = true, value_name = "PATH")]
+    history_db: Option<PathBuf>,
+
     /// Show available registries
     #[arg(long)]
     list_registries: bool,
+
+    /// Print the JSON Schema for --json output
+    #[arg(long)]
+    json_schema: bool,
+}
+
+#[derive(Args)]
+struct RegistrySelection {
+    /// Comma-separated registry IDs
+    #[arg(short, long)]
+    registries: Option<String>,
+
+    /// Filter by language
+    #[arg(short, long)]
+    languages: Option<String>,
+
+    /// Check all 29 registries
+    #[arg(short, long)]
+    all: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check that our published packages are still present (and still ours)
+    Verify {
+        /// TOML manifest of package names and the registries each should be on
+        manifest: PathBuf,
+
+        /// State every listed name is expected to be in
+        #[arg(long, value_enum, default_value = "taken")]
+        expect: Expect,
+
+        /// Registries for manifest entries that don't list their own
+        #[command(flatten)]
+        select: RegistrySelection,
+
+        /// Output results as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
+    /// Check private dependencies for dependency-confusion exposure
+    Audit {
+        /// package.json, package-lock.json, requirements.txt, pyproject.toml,
+        /// Cargo.toml or Cargo.lock
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Comma-separated name prefixes or npm scopes that are internal
+        /// (added to [audit] internal from the config)
+        #[arg(long)]
+        internal: Option<String>,
+
+        /// Output results as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
+    /// Generate name candidates from seed words and rank them by availability
+    Suggest {
+        /// Seed words
+        #[arg(required = true)]
+        seeds: Vec<String>,
+
+        /// Number of suggestions to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        top: usize,
+
+        /// Expand seeds through the bundled offline thesaurus
+        #[arg(long)]
+        synonyms: bool,
+
+        /// Also add translations into these languages (de, es, fr, it, la)
+        #[arg(long, requires = "synonyms")]
+        translate: Option<String>,
+
+        /// Most thesaurus expansions to check
+        #[arg(long, default_value_t = 30)]
+        max_expansions: usize,
+
+        #[command(flatten)]
+        select: RegistrySelection,
+
+        /// Output results as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
+    /// Stream a word list and keep the words free on every chosen registry
+    Mine {
+        /// Word list, one word per line
+        #[arg(long)]
+        wordlist: PathBuf,
+
+        /// Length range, e.g. 4..7 (inclusive), 5, or 4..
+        #[arg(long)]
+        length: Option<String>,
+
+        /// Regex every word must match
+        #[arg(long)]
+        pattern: Option<String>,
+
+        /// Most names to check per second
+        #[arg(long, default_value_t = 5.0)]
+        rate: f64,
+
+        /// Names checked concurrently per batch
+        #[arg(long, default_value_t = 10)]
+        batch: usize,
+
+        /// State file for resuming (default: <WORDLIST>.mine-state.json)
+        #[arg(long)]
+        state: Option<PathBuf>,
+
+        /// Ignore any saved state and start from the first line
+        #[arg(long)]
+        restart: bool,
+
+        /// Append free names to this file instead of printing them
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        select: RegistrySelection,
+
+        /// Write each free name's result as a JSON line
+        #[arg(short, long)]
+        json: bool,
+    },
+    /// Compare two --json reports; exit 1 if a free name became taken
+    Diff {
+        /// The earlier report
+        old: PathBuf,
+
+        /// The later report
+        new: PathBuf,
+
+        /// Output the changes as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
+    /// Show recorded checks of a name, or per-registry reliability
+    History {
+        /// Name to show the timeline of
+        #[arg(required_unless_present = "stats")]
+        name: Option<String>,
+
+        /// Latency and error rate per registry instead
+        #[arg(long, conflicts_with = "name")]
+        stats: bool,
+
+        /// Bucket --stats by day, week or month
+        #[arg(long, value_enum, default_value = "week")]
+        period: Period,
+
+        /// Output as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
+    /// Check typo and lookalike variants of a name we own
+    SquatScan {
+        /// The name to generate variants of
+        name: String,
+
+        #[command(flatten)]
+        select: RegistrySelection,
+
+        /// Output results as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
 }
 
-fn resolve_registries(cli: &Cli) -> Vec<&'static registry::Registry> {
-    if cli.all {
+fn resolve_registries(select: &RegistrySelection) -> Vec<&'static registry::Registry> {
+    if select.all {
         return registry::all_registries().iter().collect();
     }
-    if let Some(ref ids) = cli.registries {
+    if let Some(ref ids) = select.registries {
         let ids: Vec<String> = ids.split(',').map(|s| s.trim().to_string()).collect();
         return registry::registries_by_ids(&ids);
     }
-    if let Some(ref langs) = cli.languages {
+    if let Some(ref langs) = select.languages {
         let langs: Vec<String> = langs.split(',').map(|s| s.trim().to_string()).collect();
         return registry::registries_by_languages(&langs);
     }
     registry::popular_registries()
 }
 
+fn check_options(config: &Config, details: bool) -> CheckOptions {
+    CheckOptions {
+        details,
+        owners_only: false,
+        identities: Some(config.identities.clone()).filter(|ids| !ids.is_empty()),
+        scoring: config.scoring.clone(),
+        progress: None,
+    }
+}
+
+/// Names from `--input`, with unreadable lines reported on stderr.
+fn read_input(path: &Path, format: InputFormat, column: &str) -> Vec<String> {
+    let (label, text) = if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .unwrap_or_else(|e| usage_error(format!("stdin: {}", e)));
+        ("stdin".to_string(), text)
+    } else {
+        let text = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| usage_error(format!("{}: {}", path.display(), e)));
+        (path.display().to_string(), text)
+    };
+    let parsed = input::parse(&text, format, column, Some(path))
+        .unwrap_or_else(|e| usage_error(format!("{}: {}", label, e)));
+    for error in &parsed.errors {
+        eprintln!("{}: {} (skipped)", label, error);
+    }
+    parsed.names
+}
+
+/// Check `names` once each per registry normalization. With a checkpoint
+/// file, lookups it already holds are reused and new ones appended to it;
+/// with `show_progress`, a progress line is drawn on stderr meanwhile. With
+/// a `history` database, the lookups made by this run (not reused or shared
+/// ones) are recorded in it.
+async fn run_checks(
+    names: &[String],
+    registries: &[&'static registry::Registry],
+    options: &CheckOptions,
+    show_progress: bool,
+    checkpoint: Option<&Path>,
+    history: Option<&Path>,
+) -> Vec<CheckResult> {
+    let dedup = Dedup::new(names, registries);
+    let (recorded, mut writer) = match checkpoint {
+        Some(path) => {
+            let recorded = Checkpoint::load(path, options)
+                .unwrap_or_else(|e| usage_error(format!("--checkpoint {}", e)));
+            if recorded.skipped > 0 {
+                eprintln!(
+                    "{}: ignored {} unreadable line(s)",
+                    path.display(),
+                    recorded.skipped
+                );
+            }
+            if recorded.stale > 0 {
+                eprintln!(
+                    "{}: ignored {} lookup(s) made with different --details or identities",
+                    path.display(),
+                    recorded.stale
+                );
+            }
+            let writer = checkpoint::Writer::open(path, options)
+                .unwrap_or_else(|e| usage_error(format!("{}: {}", path.display(), e)));
+            (recorded, Some(writer))
+        }
+        None => (Checkpoint::default(), None),
+    };
+    let plan = recorded.remaining(dedup.plan());
+    if !recorded.is_empty() {
+        eprintln!("Resuming: reusing {} recorded lookup(s).", recorded.len());
+    }
+
+    let results = if show_progress || writer.is_some() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<Progress>();
+        let (display_sender, display_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let display = show_progress.then(|| tokio::spawn(progress::display(display_receiver)));
+        let recorder = tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                if let Some(ref mut file) = writer
+                    && let Err(e) = file.record(&event)
+                {
+                    eprintln!("checkpoint: {} (no longer recording)", e);
+                    writer = None;
+                }
+                let _ = display_sender.send(event);
+            }
+        });
+        let progress_options = CheckOptions {
+            progress: Some(sender),
+            ..options.clone()
+        };
+        let results = checker::check_planned(&plan, &progress_options).await;
+        drop(progress_options);
+        let _ = recorder.await;
+        if let Some(display) = display {
+            let _ = display.await;
+        }
+        results
+    } else {
+        checker::check_planned(&plan, options).await
+    };
+    if let Some(db) = history {
+        record_history(db, &results);
+    }
+    let results = recorded.merge(results, dedup.plan(), registries, options);
+    dedup.merge(results, options)
+}
+
+/// Report a usage problem and exit with `policy::EXIT_USAGE`.
+fn usage_error(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(policy::EXIT_USAGE);
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+async fn run_verify(
+    manifest: PathBuf,
+    expect: Expect,
+    select: RegistrySelection,
+    json: bool,
+    config: Config,
+    history: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = Manifest::load(&manifest)?;
+    let registries = resolve_registries(&select);
+    let mut options = check_options(&config, false);
+    let recorder = Recorder::start(history, &mut options);
+    let verified = verify::verify(&manifest, &registries, expect, &options).await;
+    recorder.finish(options).await;
+    let report = match verified {
+        Ok(report) => report,
+        Err(e) => usage_error(e),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "{} checked, {} ok, {} drifted",
+            report.checked,
+            report.ok,
+            report.drift.len(),
+        );
+        for drift in &report.drift {
+            let owners = if drift.owners.is_empty() {
+                String::new()
+            } else {
+                format!(" (owners: {})", drift.owners.join(", "))
+            };
+            println!(
+                "  [!] {:<20} {:<20} {}{}",
+                drift.name, drift.registry_name, drift.kind, owners,
+            );
+        }
+    }
+
+    if !report.drift.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_audit(
+    files: Vec<PathBuf>,
+    internal: Option<String>,
+    json: bool,
+    config: Config,
+    history: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut prefixes = config.audit.internal.clone();
+    if let Some(ref internal) = internal {
+        prefixes.extend(split_list(internal));
+    }
+
+    let mut deps: Vec<audit::PrivateDependency> = Vec::new();
+    for file in &files {
+        match audit::private_dependencies(file, &prefixes) {
+            Ok(found) => deps.extend(found),
+            Err(e) => usage_error(e),
+        }
+    }
+
+    let mut options = check_options(&config, false);
+    let recorder = Recorder::start(history, &mut options);
+    let report = audit::audit(&deps, &options).await;
+    recorder.finish(options).await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "{} private dependencies, {} findings",
+            report.dependencies,
+            report.findings.len(),
+        );
+        for finding in &report.findings {
+            let symbol = match finding.kind {
+                audit::FindingKind::UnclaimedPublicly => "[!]",
+                audit::FindingKind::ClaimedPublicly => "[-]",
+                audit::FindingKind::Unknown => "[?]",
+            };
+            println!(
+                "  {} {:<30} {:<10} {}",
+                symbol, finding.dependency.name, finding.result.registry_name, finding.kind,
+            );
+            println!(
+                "        {}: {}",
+                finding.dependency.source_file, finding.dependency.reason,
+            );
+        }
+    }
+
+    if !report.findings.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_squat_scan(
+    name: String,
+    select: RegistrySelection,
+    json: bool,
+    config: Config,
+    history: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let registries = resolve_registries(&select);
+    if registries.is_empty() {
+        usage_error("No matching registries found.");
+    }
+    let mut options = check_options(&config, true);
+    let recorder = Recorder::start(history, &mut options);
+    let report = squat::scan(&name, &registries, &options).await;
+    recorder.finish(options).await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "{}: {} variants, {} checks, {} taken",
+            report.name,
+            report.variants,
+            report.checks,
+            report.taken.len(),
+        );
+        for finding in &report.taken {
+            let owners = if finding.owners.is_empty() {
+                String::new()
+            } else {
+                format!(" (owners: {})", finding.owners.join(", "))
+            };
+            println!(
+                "  [-] {:<24} {:<16} {:<20}{}",
+                finding.variant,
+                finding.technique.to_string(),
+                finding.registry_name,
+                owners,
+            );
+            if let Some(ref description) = finding.description {
+                println!("        {}", description);
+            }
+        }
+    }
+
+    if !report.taken.is_empty() {
+        std::process::exit(policy::EXIT_TAKEN);
+    }
+    Ok(())
+}
+
+fn run_diff(old: PathBuf, new: PathBuf, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let before = diff::load(&old).unwrap_or_else(|e| usage_error(e));
+    let after = diff::load(&new).unwrap_or_else(|e| usage_error(e));
+    let changes = diff::diff(&before, &after);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&changes)?);
+    } else {
+        println!(
+            "{} ({}) -> {} ({})",
+            old.display(),
+            before.generated_at.format("%Y-%m-%d %H:%M"),
+            new.display(),
+            after.generated_at.format("%Y-%m-%d %H:%M"),
+        );
+        if changes.is_empty() {
+            println!("  no changes");
+        }
+        for flip in &changes.flips {
+            let marker = if flip.newly_taken() { "[!]" } else { "[~]" };
+            println!(
+                "  {} {:<24} {:<12} {} -> {}",
+                marker,
+                flip.name,
+                flip.registry_id,
+                table::status_key(&flip.from),
+                table::status_key(&flip.to),
+            );
+        }
+        for error in &changes.new_errors {
+            println!(
+                "  [?] {:<24} {:<12} {}",
+                error.name, error.registry_id, error.error
+            );
+        }
+        for (label, list) in [
+            ("registries added", &changes.added_registries),
+            ("registries removed", &changes.removed_registries),
+            ("names added", &changes.added_names),
+            ("names removed", &changes.removed_names),
+        ] {
+            if !list.is_empty() {
+                println!("  {}: {}", label, list.join(", "));
+            }
+        }
+    }
+
+    if changes.newly_taken().next().is_some() {
+        std::process::exit(policy::EXIT_TAKEN);
+    }
+    Ok(())
+}
+
+/// `--history-db`, else the config's `[history] path`, else the default.
+fn history_path(flag: Option<&Path>, config: &Config) -> PathBuf {
+    flag.map(Path::to_path_buf)
+        .or_else(|| config.history.db_path())
+        .or_else(history::default_path)
+        .unwrap_or_else(|| usage_error("No history database path: set --history-db or HOME."))
+}
+
+/// Add a run's results to the history database. A failure is reported but
+/// doesn't fail the run.
+fn record_history(db: &Path, results: &[CheckResult]) {
+    let recorded = History::open(db).and_then(|mut h| h.record(results, chrono::Utc::now()));
+    if let Err(e) = recorded {
+        eprintln!("history: {}", e);
+    }
+}
+
+/// Records a subcommand's lookups in the history database as they finish.
+struct Recorder(Option<tokio::task::JoinHandle<()>>);
+
+impl Recorder {
+    /// With a `db`, send every lookup made with `options` to it. A database
+    /// that can't be opened is reported but doesn't fail the run.
+    fn start(db: Option<&Path>, options: &mut CheckOptions) -> Recorder {
+        let Some(db) = db else {
+            return Recorder(None);
+        };
+        let mut history = match History::open(db) {
+            Ok(history) => history,
+            Err(e) => {
+                eprintln!("history: {}", e);
+                return Recorder(None);
+            }
+        };
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<Progress>();
+        options.progress = Some(sender);
+        Recorder(Some(tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                if let Progress::Finished { name, result } = event
+                    && let Err(e) = history.record_lookup(&name, &result, chrono::Utc::now())
+                {
+                    eprintln!("history: {} (no longer recording)", e);
+                    return;
+                }
+            }
+        })))
+    }
+
+    /// Drop `options` (closing the channel) and wait for queued lookups to
+    /// be written.
+    async fn finish(self, options: CheckOptions) {
+        drop(options);
+        if let Some(task) = self.0 {
+            let _ = task.await;
+        }
+    }
+}
+
+fn run_history(
+    db: &Path,
+    name: Option<String>,
+    stats: bool,
+    period: Period,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !db.exists() {
+        usage_error(format!(
+            "{}: no history yet (run a check with --record)",
+            db.display()
+        ));
+    }
+    let history = History::open(db).unwrap_or_else(|e| usage_error(e));
+
+    if stats {
+        let rows = history.stats(period)?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+            return Ok(());
+        }
+        println!(
+            "  {:<18} {:<10} {:>7} {:>7} {:>9} {:>9}",
+            "REGISTRY", "PERIOD", "CHECKS", "ERRORS", "MEAN MS", "MAX MS"
+        );
+        for row in &rows {
+            println!(
+                "  {:<18} {:<10} {:>7} {:>6.1}% {:>9.0} {:>9}",
+                row.registry_id,
+                row.period,
+                row.checks,
+                row.error_rate * 100.0,
+                row.mean_ms,
+                row.max_ms,
+            );
+        }
+        return Ok(());
+    }
+
+    let name = name.expect("clap requires a name without --stats");
+    let spans = history.timeline(&name)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&spans)?);
+        return Ok(());
+    }
+    if spans.is_empty() {
+        println!("{}: no recorded checks", name);
+        return Ok(());
+    }
+    println!("{}:", name);
+    let mut registry = "";
+    for span in &spans {
+        let label = if span.registry_id != registry {
+            registry = &span.registry_id;
+            registry
+        } else {
+            ""
+        };
+        let when = if span.first == span.last {
+            span.first.format("%Y-%m-%d %H:%M").to_string()
+        } else {
+            format!(
+                "{} .. {}",
+                span.first.format("%Y-%m-%d %H:%M"),
+                span.last.format("%Y-%m-%d %H:%M")
+            )
+        };
+        println!(
+            "  {:<18} {:<13} {}  ({} check{})",
+            label,
+            span.status,
+            when,
+            span.checks,
+            if span.checks == 1 { "" } else { "s" },
+        );
+    }
+    Ok(())
+}
+
+struct MineArgs {
+    wordlist: PathBuf,
+    length: Option<String>,
+    pattern: Option<String>,
+    rate: f64,
+    batch: usize,
+    state: Option<PathBuf>,
+    restart: bool,
+    output: Option<PathBuf>,
+}
+
+async fn run_mine(
+    args: MineArgs,
+    select: RegistrySelection,
+    json: bool,
+    config: Config,
+    history: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let registries = resolve_registries(&select);
+    if registries.is_empty() {
+        usage_error("No matching registries found.");
+    }
+    let (min_len, max_len) = match args.length.as_deref().map(mine::parse_length) {
+        None => (1, usize::MAX),
+        Some(Ok(range)) => range,
+        Some(Err(e)) => usage_error(e),
+    };
+    let pattern = match args.pattern.as_deref().map(regex::Regex::new) {
+        None => None,
+        Some(Ok(re)) => Some(re),
+        Some(Err(e)) => usage_error(format!("Invalid --pattern: {}", e)),
+    };
+    let constraints = Constraints {
+        min_len,
+        max_len,
+        pattern,
+    };
+
+    let state_path = args
+        .state
+        .unwrap_or_else(|| PathBuf::from(format!("{}.mine-state.json", args.wordlist.display())));
+    let fresh = MineState::new(
+        &args.wordlist,
+        &format!(
+            "length={} pattern={}",
+            args.length.as_deref().unwrap_or(".."),
+            args.pattern.as_deref().unwrap_or(""),
+        ),
+        &registries,
+    );
+    let mut state = match MineState::load(&state_path)? {
+        Some(saved) if !args.restart => {
+            if !saved.matches(&fresh) {
+                usage_error(format!(
+                    "{} was saved by a different search; pass --restart to start over.",
+                    state_path.display(),
+                ));
+            }
+            eprintln!(
+                "Resuming from line {} ({} free so far).",
+                saved.line, saved.free
+            );
+            saved
+        }
+        _ => fresh,
+    };
+    let resuming = state.line > 0;
+
+    let mut out: Box<dyn Write> = match args.output {
+        Some(ref path) => Box::new(
+            std::fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(resuming)
+                .truncate(!resuming)
+                .open(path)
+                .map_err(|e| format!("{}: {}", path.display(), e))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+    let tty = std::io::stderr().is_terminal();
+    let pace = Pace {
+        rate: args.rate,
+        batch: args.batch,
+    };
+    let mut options = check_options(&config, false);
+    let recorder = Recorder::start(history, &mut options);
+
+    let mined = mine::mine(
+        &args.wordlist,
+        &constraints,
+        &registries,
+        pace,
+        &options,
+        &mut state,
+        |state, free| {
+            for result in free {
+                if json {
+                    writeln!(out, "{}", serde_json::to_string(result)?)?;
+                } else {
+                    writeln!(out, "{}", result.name)?;
+                }
+            }
+            out.flush()?;
+            state.save(&state_path)?;
+            let progress = format!(
+                "line {}: {} checked, {} free, {} unknown, {} skipped",
+                state.line, state.checked, state.free, state.unknown, state.skipped,
+            );
+            if tty {
+                eprint!("\r{}\x1b[K", progress);
+            } else {
+                eprintln!("{}", progress);
+            }
+            Ok(())
+        },
+    )
+    .await;
+    recorder.finish(options).await;
+    mined?;
+
+    if tty {
+        eprintln!();
+    }
+    eprintln!(
+        "Done: {} free of {} checked ({} unknown). State kept in {}.",
+        state.free,
+        state.checked,
+        state.unknown,
+        state_path.display(),
+    );
+    Ok(())
+}
+
+struct SuggestArgs {
+    seeds: Vec<String>,
+    top: usize,
+    synonyms: bool,
+    translate: Option<String>,
+    max_expansions: usize,
+}
+
+async fn run_suggest(
+    args: SuggestArgs,
+    select: RegistrySelection,
+    json: bool,
+    config: Config,
+    history: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let registries = resolve_registries(&select);
+    if registries.is_empty() {
+        usage_error("No matching registries found.");
+    }
+
+    let mut candidates = suggest::candidates(&args.seeds);
+    if args.synonyms {
+        let langs = args
+            .translate
+            .as_deref()
+            .map(split_list)
+            .unwrap_or_default();
+        if let Some(lang) = langs
+            .iter()
+            .find(|l| !thesaurus::languages().contains(&l.as_str()))
+        {
+            usage_error(format!(
+                "No word list for '{}' (available: {}).",
+                lang,
+                thesaurus::languages().join(", "),
+            ));
+        }
+        let expansions = thesaurus::expand(&args.seeds, &langs);
+        let extra = suggest::expansion_candidates(&expansions, &candidates, args.max_expansions);
+        candidates.extend(extra);
+    }
+
+    let mut options = check_options(&config, false);
+    let recorder = Recorder::start(history, &mut options);
+    let report = suggest::rank(candidates, &args.seeds, &registries, args.top, &options).await;
+    recorder.finish(options).await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} candidates generated, {} checked",
+        report.generated, report.checked,
+    );
+    for (i, suggestion) in report.suggestions.iter().enumerate() {
+        let summary = &suggestion.result.summary;
+        println!(
+            "  {:>2}. {:<24} score {:>3.0}  {:>2}/{:<2} available  ({})",
+            i + 1,
+            suggestion.name,
+            suggestion.result.score.value,
+            summary.available,
+            summary.total,
+            suggestion.source,
+        );
+    }
+    Ok(())
+}
+
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
+async fn main() {
+    // Errors that reach here are bad files or paths (a manifest that won't
+    // parse, an --output that can't be written), so they exit like usage
+    // errors rather than as a finding.
+    if let Err(e) = run().await {
+        usage_error(e);
+    }
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::try_parse().unwrap_or_else(|e| {
+        let code = if e.use_stderr() {
+            policy::EXIT_USAGE
+        } else {
+            policy::EXIT_CLEAR
+        };
+        let _ = e.print();
+        std::process::exit(code);
+    });
+    let mut config = Config::load(cli.config.as_deref()).unwrap_or_else(|e| usage_error(e));
+    let history_db = (cli.record || config.history.record)
+        .then(|| history_path(cli.history_db.as_deref(), &config));
+
+    if let Some(command) = cli.command {
+        return match command {
+            Command::Verify {
+                manifest,
+                expect,
+                select,
+                json,
+            } => {
+                run_verify(
+                    manifest,
+                    expect,
+                    select,
+                    json,
+                    config,
+                    history_db.as_deref(),
+                )
+                .await
+            }
+            Command::Audit {
+                files,
+                internal,
+                json,
+            } => run_audit(files, internal, json, config, history_db.as_deref()).await,
+            Command::Suggest {
+                seeds,
+                top,
+                synonyms,
+                translate,
+                max_expansions,
+                select,
+                json,
+            } => {
+                let args = SuggestArgs {
+                    seeds,
+                    top,
+                    synonyms,
+                    translate,
+                    max_expansions,
+                };
+                run_suggest(args, select, json, config, history_db.as_deref()).await
+            }
+            Command::Mine {
+                wordlist,
+                length,
+                pattern,
+                rate,
+                batch,
+                state,
+                restart,
+                output,
+                select,
+                json,
+            } => {
+                let args = MineArgs {
+                    wordlist,
+                    length,
+                    pattern,
+                    rate,
+                    batch,
+                    state,
+                    restart,
+                    output,
+                };
+                run_mine(args, select, json, config, history_db.as_deref()).await
+            }
+            Command::Diff { old, new, json } => run_diff(old, new, json),
+            Command::History {
+                name,
+                stats,
+                period,
+                json,
+            } => {
+                let db = history_path(cli.history_db.as_deref(), &config);
+                run_history(&db, name, stats, period, json)
+            }
+            Command::SquatScan { name, select, json } => {
+                run_squat_scan(name, select, json, config, history_db.as_deref()).await
+            }
+        };
+    }
+
+    if cli.json_schema {
+        let schema = schemars::schema_for!(Report);
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+        return Ok(());
+    }
 
     if cli.list_registries {
         println!("{:<20} {:<25} {:<30} LANGUAGES", "ID", "NAME", "ECOSYSTEM");
@@ -76,71 +1114,257 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    if cli.names.is_empty() {
+    let mut names = cli.names.clone();
+    if let Some(ref path) = cli.input {
+        names.extend(read_input(path, cli.input_format, &cli.column));
+    }
+    if names.is_empty() {
         eprintln!("Usage: staked [OPTIONS] <NAMES>...");
         eprintln!("       staked --list-registries");
+        eprintln!("       staked verify [OPTIONS] <MANIFEST>");
+        eprintln!("       staked audit [OPTIONS] <FILES>...");
+        eprintln!("       staked suggest [OPTIONS] <SEEDS>...");
+        eprintln!("       staked squat-scan [OPTIONS] <NAME>");
+        eprintln!("       staked mine --wordlist <FILE> [OPTIONS]");
         eprintln!();
         eprintln!("Run 'staked --help' for more information.");
-        std::process::exit(1);
+        std::process::exit(policy::EXIT_USAGE);
     }
 
-    let registries = resolve_registries(&cli);
+    let mut registries = resolve_registries(&cli.select);
     if registries.is_empty() {
-        eprintln!("No matching registries found.");
-        std::process::exit(1);
+        usage_error("No matching registries found.");
+    }
+    if let Some(ref ids) = cli.require {
+        let ids = split_list(ids);
+        let required = registry::registries_by_ids(&ids);
+        if let Some(missing) = ids.iter().find(|id| !required.iter().any(|r| r.id == *id)) {
+            usage_error(format!("Unknown registry '{}' in --require.", missing));
+        }
+        for reg in required {
+            if !registries.iter().any(|r| r.id == reg.id) {
+                registries.push(reg);
+            }
+            if !config.scoring.required.iter().any(|id| id == reg.id) {
+                config.scoring.required.push(reg.id.to_string());
+            }
+        }
+    }
+    let gated = !config.scoring.required.is_empty() || !cli.fail_on.is_empty();
+
+    let template = match (&cli.template, &cli.template_file) {
+        (Some(row), _) => Some(UserTemplate::Rows(
+            Template::parse(&template::unescape(row)).unwrap_or_else(|e| usage_error(e)),
+        )),
+        (None, Some(path)) => {
+            let source = std::fs::read_to_string(path)
+                .unwrap_or_else(|e| usage_error(format!("{}: {}", path.display(), e)));
+            Some(UserTemplate::Whole(
+                Template::parse(&source)
+                    .unwrap_or_else(|e| usage_error(format!("{}: {}", path.display(), e))),
+            ))
+        }
+        (None, None) => None,
+    };
+    let format = if cli.json { Format::Json } else { cli.format };
+    if format == Format::Text && template.is_none() && cli.output.is_some() {
+        usage_error("--output needs a --format other than text.");
+    }
+    let options = check_options(&config, cli.details);
+    let style = Style::detect(cli.color);
+    let show_progress = format != Format::Json && std::io::stderr().is_terminal();
+    let report_options = ReportOptions {
+        details: cli.details,
+        variants: cli.variants,
+        required: config.scoring.required.clone(),
+        fail_on: cli.fail_on.clone(),
+        weights: config.scoring.weights.clone().into_iter().collect(),
+    };
+    if cli.variants {
+        let mut origins: Vec<(String, String)> = Vec::new();
+        for input in &names {
+            for variant in variants::expand(input) {
+                origins.push((variant, input.trim().to_string()));
+            }
+        }
+        let variant_names: Vec<String> = origins.iter().map(|(name, _)| name.clone()).collect();
+        let checked = run_checks(
+            &variant_names,
+            &registries,
+            &options,
+            show_progress,
+            cli.checkpoint.as_deref(),
+            history_db.as_deref(),
+        )
+        .await;
+        let results = variants::tag(&checked, &origins);
+
+        if let Some(ref template) = template {
+            emit_template(template, &results, cli.output.as_deref())?;
+        } else if format != Format::Text {
+            emit(
+                format,
+                &results,
+                &registries,
+                &report_options,
+                cli.output.as_deref(),
+            )?;
+        } else {
+            for input in &names {
+                let group: Vec<&CheckResult> = results
+                    .iter()
+                    .filter(|r| r.variant_of.as_deref() == Some(input.trim()))
+                    .collect();
+                println!("{} variants:", input.trim());
+                print!(
+                    "{}",
+                    terminal::name_rows("VARIANT", &group, &registries, &style)
+                );
+            }
+        }
+        if gated {
+            exit_with_verdict(&results, &cli.fail_on);
+        }
+        return Ok(());
     }
 
-    let results = checker::check_packages(&cli.names, &registries).await;
+    let mut results = run_checks(
+        &names,
+        &registries,
+        &options,
+        show_progress,
+        cli.checkpoint.as_deref(),
+        history_db.as_deref(),
+    )
+    .await;
+    let ranking = if results.len() > 1 {
+        score::rank(&mut results)
+    } else {
+        Vec::new()
+    };
 
-    if cli.json {
-        println!("{}", serde_json::to_string_pretty(&results)?);
+    if let Some(ref template) = template {
+        emit_template(template, &results, cli.output.as_deref())?;
+    } else if format != Format::Text {
+        emit(
+            format,
+            &results,
+            &registries,
+            &report_options,
+            cli.output.as_deref(),
+        )?;
+    } else if cli.matrix {
+        print!("{}", terminal::matrix(&results, &registries, &style));
+        if !ranking.is_empty() {
+            print!("{}", terminal::ranking(&results, &ranking, &style));
+        }
+    } else if cli.compact {
+        print!("{}", terminal::compact(&results, &style));
     } else {
         for result in &results {
-            println!("{}:", result.name);
-            println!(
-                "  {} available, {} taken, {} unknown ({}ms)",
-                result.summary.available,
-                result.summary.taken,
-                result.summary.unknown,
-                result.elapsed_ms,
-            );
+            print!("{}", terminal::result_block(result, cli.verbose, &style));
+        }
+        if !ranking.is_empty() {
+            print!("{}", terminal::ranking(&results, &ranking, &style));
+        }
+    }
 
-            if cli.verbose {
-                for pkg in &result.results {
-                    let symbol = match pkg.available {
-                        Availability::Available => "[+]",
-                        Availability::Taken => "[-]",
-                        Availability::Unknown => "[?]",
-                    };
-                    println!(
-                        "  {} {:<20} {:<12} ({}ms)",
-                        symbol, pkg.registry_name, pkg.available, pkg.elapsed_ms,
-                    );
-                }
-            } else {
-                let available: Vec<&str> = result
-                    .results
-                    .iter()
-                    .filter(|r| r.available == Availability::Available)
-                    .map(|r| r.registry_name.as_str())
-                    .collect();
-                let taken: Vec<&str> = result
-                    .results
-                    .iter()
-                    .filter(|r| r.available == Availability::Taken)
-                    .map(|r| r.registry_name.as_str())
-                    .collect();
+    if gated {
+        exit_with_verdict(&results, &cli.fail_on);
+    }
+    Ok(())
+}
 
-                if !available.is_empty() {
-                    println!("  available: {}", available.join(", "));
-                }
-                if !taken.is_empty() {
-                    println!("  taken: {}", taken.join(", "));
-                }
-            }
-            println!();
+/// A user template from --template (per row) or --template-file (whole report).
+enum UserTemplate {
+    Rows(Template),
+    Whole(Template),
+}
+
+fn emit_template(
+    report: &UserTemplate,
+    results: &[CheckResult],
+    output: Option<&std::path::Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rendered = match report {
+        UserTemplate::Rows(template) => template.render_rows(results),
+        UserTemplate::Whole(template) => template.render_report(results, chrono::Utc::now()),
+    }
+    .unwrap_or_else(|e| usage_error(e));
+    write_report(&rendered, output)
+}
+
+fn write_report(
+    rendered: &str,
+    output: Option<&std::path::Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match output {
+        Some(path) => {
+            std::fs::write(path, rendered).map_err(|e| format!("{}: {}", path.display(), e))?
         }
+        None => print!("{}", rendered),
     }
+    Ok(())
+}
 
+/// Render results in one of the machine-readable formats and print them, or
+/// write them to `output`.
+fn emit(
+    format: Format,
+    results: &[CheckResult],
+    registries: &[&'static registry::Registry],
+    options: &ReportOptions,
+    output: Option<&std::path::Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rendered = match format {
+        Format::Text => unreachable!("text output is printed by main"),
+        Format::Json => {
+            let report = Report::new(
+                results.to_vec(),
+                registries.iter().map(|r| r.id.to_string()).collect(),
+                options.clone(),
+                chrono::Utc::now(),
+            );
+            serde_json::to_string_pretty(&report)? + "\n"
+        }
+        Format::Junit => output::junit::render(results),
+        Format::Csv => output::table::csv(results),
+        Format::Tsv => output::table::tsv(results),
+        Format::Markdown => output::table::markdown(results),
+        Format::MarkdownPivot => output::markdown_pivot(results, registries),
+        Format::Html => output::html::render(results, registries, chrono::Utc::now()),
+        Format::Github => {
+            if let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .map_err(|e| format!("{}: {}", PathBuf::from(&path).display(), e))?;
+                write!(
+                    file,
+                    "{}",
+                    output::github::step_summary(results, registries)
+                )?;
+            }
+            output::github::annotations(results)
+        }
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(path, rendered).map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        None => print!("{}", rendered),
+    }
     Ok(())
 }
+
+/// Exit with the policy's code, listing violations on stderr.
+fn exit_with_verdict(results: &[CheckResult], fail_on: &[FailOn]) {
+    let verdict = policy::evaluate(results, fail_on);
+    for reason in &verdict.reasons {
+        eprintln!("{}", reason);
+    }
+    if verdict.exit_code != policy::EXIT_CLEAR {
+        std::process::exit(verdict.exit_code);
+    }
+}
@@ -0,0 +1,404 @@
+use crate::registry::{MetadataFormat, Registry};
+use crate::types::{Downloads, TakenDetails};
+use reqwest::Client;
+use serde_json::Value;
+
+/// Build `TakenDetails` for a taken name from the check response body, making
+/// follow-up requests for owners or download counts where the registry keeps
+/// them on a separate endpoint. With `owners_only`, download counts and the
+/// GitHub archived check are skipped. Returns `None` for registries without a
+/// known metadata format or when nothing useful could be extracted.
+pub async fn fetch_details(
+    client: &Client,
+    registry: &Registry,
+    name: &str,
+    body: Option<&str>,
+    owners_only: bool,
+) -> Option<TakenDetails> {
+    if registry.metadata == MetadataFormat::None {
+        return None;
+    }
+    let json: Value = serde_json::from_str(body?.trim()).ok()?;
+    let mut details = parse_details(registry.metadata, &json);
+
+    match registry.metadata {
+        MetadataFormat::Npm if !owners_only => {
+            let url = format!("https://api.npmjs.org/downloads/point/last-week/{name}");
+            if let Some(count) = fetch_json(client, &url)
+                .await
+                .and_then(|v| v.get("downloads").and_then(Value::as_u64))
+            {
+                details.downloads = Some(Downloads {
+                    count,
+                    period: "last-week".to_string(),
+                });
+            }
+        }
+        MetadataFormat::Crates => {
+            let url = format!("https://crates.io/api/v1/crates/{name}/owners");
+            if let Some(users) = fetch_json(client, &url).await {
+                details.owners = string_list(users.get("users"), "login");
+            }
+        }
+        MetadataFormat::RubyGems => {
+            let url = format!("https://rubygems.org/api/v1/gems/{name}/owners.json");
+            if let Some(owners) = fetch_json(client, &url).await {
+                details.owners = string_list(Some(&owners), "handle");
+            }
+        }
+        MetadataFormat::Hex => {
+            let url = format!("https://hex.pm/api/packages/{name}/owners");
+            if let Some(owners) = fetch_json(client, &url).await {
+                details.owners = string_list(Some(&owners), "username");
+            }
+        }
+        MetadataFormat::Pub => {
+            let url = format!("https://pub.dev/api/packages/{name}/publisher");
+            if let Some(publisher) = fetch_json(client, &url)
+                .await
+                .and_then(|v| str_at(&v, &["publisherId"]))
+            {
+                details.owners = vec![publisher];
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(repo) = details
+        .repository
+        .as_deref()
+        .and_then(|u| u.strip_prefix("https://github.com/"))
+        .filter(|_| !owners_only)
+    {
+        details.repository_archived = github_archived(client, repo).await;
+    }
+
+    if is_empty(&details) {
+        None
+    } else {
+        Some(details)
+    }
+}
+
+/// Extract whatever the check response itself carries, without any network.
+pub fn parse_details(format: MetadataFormat, json: &Value) -> TakenDetails {
+    match format {
+        MetadataFormat::None => TakenDetails::default(),
+        MetadataFormat::Npm => {
+            let latest = str_at(json, &["dist-tags", "latest"]);
+            let last_published = latest
+                .as_deref()
+                .and_then(|v| str_at(json, &["time", v]))
+                .or_else(|| str_at(json, &["time", "modified"]));
+            TakenDetails {
+                description: str_at(json, &["description"]),
+                latest_version: latest,
+                last_published,
+                repository: str_at(json, &["repository", "url"])
+                    .or_else(|| str_at(json, &["repository"]))
+                    .map(|u| clean_repository_url(&u)),
+                homepage: str_at(json, &["homepage"]),
+                owners: string_list(json.get("maintainers"), "name"),
+                yanked: Some(json.pointer("/time/unpublished").is_some()),
+                downloads: None,
+                release_count: json
+                    .get("versions")
+                    .and_then(Value::as_object)
+                    .map(|v| v.len()),
+                repository_archived: None,
+            }
+        }
+        MetadataFormat::PyPI => {
+            let info = json.get("info").unwrap_or(&Value::Null);
+            let repository = ["Source", "Source Code", "Repository", "Code", "GitHub"]
+                .iter()
+                .find_map(|key| str_at(info, &["project_urls", key]));
+            let homepage = ["Homepage", "Home", "homepage"]
+                .iter()
+                .find_map(|key| str_at(info, &["project_urls", key]))
+                .or_else(|| str_at(info, &["home_page"]));
+            let owners = ["maintainer", "author"]
+                .iter()
+                .filter_map(|key| str_at(info, &[key]))
+                .collect::<Vec<_>>();
+            TakenDetails {
+                description: str_at(info, &["summary"]),
+                latest_version: str_at(info, &["version"]),
+                last_published: json
+                    .get("urls")
+                    .and_then(Value::as_array)
+                    .and_then(|files| {
+                        files
+                            .iter()
+                            .filter_map(|f| str_at(f, &["upload_time_iso_8601"]))
+                            .max()
+                    }),
+                repository: repository.map(|u| clean_repository_url(&u)),
+                homepage,
+                owners: dedup(owners),
+                yanked: json
+                    .get("urls")
+                    .and_then(Value::as_array)
+                    .filter(|files| !files.is_empty())
+                    .map(|files| {
+                        files
+                            .iter()
+                            .all(|f| f.get("yanked").and_then(Value::as_bool) == Some(true))
+                    }),
+                downloads: None,
+                release_count: json
+                    .get("releases")
+                    .and_then(Value::as_object)
+                    .map(|v| v.len()),
+                repository_archived: None,
+            }
+        }
+        MetadataFormat::Crates => {
+            let krate = json.get("crate").unwrap_or(&Value::Null);
+            TakenDetails {
+                description: str_at(krate, &["description"]),
+                latest_version: str_at(krate, &["max_stable_version"])
+                    .or_else(|| str_at(krate, &["newest_version"]))
+                    .or_else(|| str_at(krate, &["max_version"])),
+                last_published: str_at(krate, &["updated_at"]),
+                repository: str_at(krate, &["repository"]).map(|u| clean_repository_url(&u)),
+                homepage: str_at(krate, &["homepage"]),
+                owners: Vec::new(),
+                downloads: krate
+                    .get("downloads")
+                    .and_then(Value::as_u64)
+                    .map(|count| Downloads {
+                        count,
+                        period: "total".to_string(),
+                    }),
+                release_count: array_len(json.get("versions")),
+                repository_archived: None,
+                yanked: json
+                    .get("versions")
+                    .and_then(Value::as_array)
+                    .filter(|versions| !versions.is_empty())
+                    .map(|versions| {
+                        versions
+                            .iter()
+                            .all(|v| v.get("yanked").and_then(Value::as_bool) == Some(true))
+                    }),
+            }
+        }
+        MetadataFormat::RubyGems => TakenDetails {
+            description: str_at(json, &["info"]),
+            latest_version: str_at(json, &["version"]),
+            last_published: str_at(json, &["version_created_at"]),
+            repository: str_at(json, &["source_code_uri"]).map(|u| clean_repository_url(&u)),
+            homepage: str_at(json, &["homepage_uri"]),
+            owners: Vec::new(),
+            downloads: json
+                .get("downloads")
+                .and_then(Value::as_u64)
+                .map(|count| Downloads {
+                    count,
+                    period: "total".to_string(),
+                }),
+            ..TakenDetails::default()
+        },
+        MetadataFormat::NuGet => TakenDetails {
+            latest_version: json
+                .get("versions")
+                .and_then(Value::as_array)
+                .and_then(|versions| versions.last())
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            release_count: array_len(json.get("versions")),
+            ..TakenDetails::default()
+        },
+        MetadataFormat::Hex => {
+            let links = json.pointer("/meta/links").and_then(Value::as_object);
+            let link = |keys: &[&str]| {
+                links.and_then(|links| {
+                    links
+                        .iter()
+                        .find(|(k, _)| keys.contains(&k.to_lowercase().as_str()))
+                        .and_then(|(_, v)| v.as_str())
+                        .map(str::to_string)
+                })
+            };
+            let repository = link(&["github", "gitlab", "source", "repository"])
+                .map(|u| clean_repository_url(&u));
+            let homepage = link(&["homepage", "website", "docs"]);
+            TakenDetails {
+                description: str_at(json, &["meta", "description"]),
+                latest_version: str_at(json, &["latest_stable_version"])
+                    .or_else(|| str_at(json, &["latest_version"])),
+                last_published: str_at(json, &["updated_at"]),
+                repository,
+                homepage,
+                owners: Vec::new(),
+                downloads: json
+                    .pointer("/downloads/all")
+                    .and_then(Value::as_u64)
+                    .map(|count| Downloads {
+                        count,
+                        period: "total".to_string(),
+                    }),
+                release_count: array_len(json.get("releases")),
+                repository_archived: None,
+                yanked: json
+                    .get("retirements")
+                    .and_then(Value::as_object)
+                    .zip(str_at(json, &["latest_version"]))
+                    .map(|(retired, latest)| retired.contains_key(&latest)),
+            }
+        }
+        MetadataFormat::Pub => TakenDetails {
+            description: str_at(json, &["latest", "pubspec", "description"]),
+            latest_version: str_at(json, &["latest", "version"]),
+            last_published: str_at(json, &["latest", "published"]),
+            repository: str_at(json, &["latest", "pubspec", "repository"])
+                .map(|u| clean_repository_url(&u)),
+            homepage: str_at(json, &["latest", "pubspec", "homepage"]),
+            release_count: array_len(json.get("versions")),
+            ..TakenDetails::default()
+        },
+        MetadataFormat::Homebrew => {
+            let name = str_at(json, &["name"]).unwrap_or_default();
+            TakenDetails {
+                description: str_at(json, &["desc"]),
+                latest_version: str_at(json, &["versions", "stable"])
+                    .or_else(|| str_at(json, &["version"])),
+                last_published: None,
+                repository: None,
+                homepage: str_at(json, &["homepage"]),
+                owners: Vec::new(),
+                downloads: json
+                    .get("analytics")
+                    .and_then(|a| a.get("install"))
+                    .and_then(|a| a.get("365d"))
+                    .and_then(|a| a.get(&name))
+                    .and_then(Value::as_u64)
+                    .map(|count| Downloads {
+                        count,
+                        period: "365d".to_string(),
+                    }),
+                ..TakenDetails::default()
+            }
+        }
+        MetadataFormat::DockerHub => TakenDetails {
+            description: str_at(json, &["description"]),
+            latest_version: None,
+            last_published: str_at(json, &["last_updated"]),
+            repository: None,
+            owners: str_at(json, &["namespace"]).into_iter().collect(),
+            downloads: json
+                .get("pull_count")
+                .and_then(Value::as_u64)
+                .map(|count| Downloads {
+                    count,
+                    period: "total".to_string(),
+                }),
+            ..TakenDetails::default()
+        },
+        MetadataFormat::CocoaPods => {
+            let versions = json.get("versions").and_then(Value::as_array);
+            let latest = versions.and_then(|v| v.last());
+            TakenDetails {
+                latest_version: latest.and_then(|v| str_at(v, &["name"])),
+                last_published: latest.and_then(|v| str_at(v, &["created_at"])),
+                owners: string_list(json.get("owners"), "name"),
+                release_count: versions.map(Vec::len),
+                ..TakenDetails::default()
+            }
+        }
+    }
+}
+
+/// Normalize a repository URL as registries report it (`git+https://...git`,
+/// `git://...`, `git@github.com:...`) into a plain browsable URL.
+pub fn clean_repository_url(url: &str) -> String {
+    let mut url = url.trim().trim_start_matches("git+").to_string();
+    if let Some(rest) = url.strip_prefix("git@") {
+        url = format!("https://{}", rest.replacen(':', "/", 1));
+    } else if let Some(rest) = url.strip_prefix("git://") {
+        url = format!("https://{rest}");
+    } else if let Some(rest) = url.strip_prefix("ssh://git@") {
+        url = format!("https://{rest}");
+    } else if let Some(rest) = url.strip_prefix("github:") {
+        url = format!("https://github.com/{rest}");
+    }
+    let url = url.trim_end_matches('/');
+    url.strip_suffix(".git").unwrap_or(url).to_string()
+}
+
+/// Look up whether a GitHub repository is archived. Uses `GITHUB_TOKEN` when
+/// set, since the anonymous API limit is low.
+async fn github_archived(client: &Client, repo: &str) -> Option<bool> {
+    let mut parts = repo.split('/');
+    let (owner, name) = (parts.next()?, parts.next()?);
+    let mut request = client.get(format!("https://api.github.com/repos/{owner}/{name}"));
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let json: Value = response.json().await.ok()?;
+    json.get("archived").and_then(Value::as_bool)
+}
+
+async fn fetch_json(client: &Client, url: &str) -> Option<Value> {
+    let response = client.get(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().await.ok()
+}
+
+fn str_at(json: &Value, path: &[&str]) -> Option<String> {
+    let mut current = json;
+    for key in path {
+        current = current.get(key)?;
+    }
+    current
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Collect `key` from each object in an array, or plain strings if the array
+/// holds strings directly.
+fn string_list(json: Option<&Value>, key: &str) -> Vec<String> {
+    let Some(items) = json.and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    dedup(
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(s.clone()),
+                _ => str_at(item, &[key]),
+            })
+            .collect(),
+    )
+}
+
+fn array_len(json: Option<&Value>) -> Option<usize> {
+    json.and_then(Value::as_array).map(Vec::len)
+}
+
+fn dedup(mut items: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    items.retain(|item| seen.insert(item.clone()));
+    items
+}
+
+fn is_empty(details: &TakenDetails) -> bool {
+    details.description.is_none()
+        && details.latest_version.is_none()
+        && details.last_published.is_none()
+        && details.repository.is_none()
+        && details.homepage.is_none()
+        && details.owners.is_empty()
+        && details.downloads.is_none()
+        && details.release_count.is_none()
+        && details.yanked.is_none()
+}
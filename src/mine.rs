@@ -0,0 +1,195 @@
+use crate::checker::{self, CheckOptions};
+use crate::registry::{self, Registry};
+use crate::types::CheckResult;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Which words from the list are worth checking.
+#[derive(Debug, Clone)]
+pub struct Constraints {
+    pub min_len: usize,
+    pub max_len: usize,
+    pub pattern: Option<Regex>,
+}
+
+impl Constraints {
+    /// True if `word` fits the length range, matches the pattern, and is a
+    /// valid name on every registry.
+    pub fn allows(&self, word: &str, registries: &[&'static Registry]) -> bool {
+        let len = word.chars().count();
+        len >= self.min_len
+            && len <= self.max_len
+            && self.pattern.as_ref().is_none_or(|p| p.is_match(word))
+            && registries.iter().all(|r| registry::is_valid_name(r, word))
+    }
+}
+
+/// Parse a length range: `4..7` and `4..=7` are both inclusive, `4..` has no
+/// upper bound, `..7` no lower bound, and a bare `5` is an exact length.
+pub fn parse_length(range: &str) -> Result<(usize, usize), String> {
+    let parse = |s: &str, default: usize| -> Result<usize, String> {
+        if s.is_empty() {
+            return Ok(default);
+        }
+        s.parse()
+            .map_err(|_| format!("invalid length range '{}'", range))
+    };
+    let (min, max) = match range.split_once("..") {
+        Some((min, max)) => (
+            parse(min.trim(), 1)?,
+            parse(max.trim().trim_start_matches('='), usize::MAX)?,
+        ),
+        None => {
+            let n = parse(range.trim(), 0)?;
+            (n, n)
+        }
+    };
+    if min > max {
+        return Err(format!("invalid length range '{}'", range));
+    }
+    Ok((min, max))
+}
+
+/// Where a run got to, saved after every batch so an interrupted run can
+/// pick up again. `constraints` and `registries` are recorded so a state
+/// file isn't resumed against a different search.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MineState {
+    pub wordlist: String,
+    pub constraints: String,
+    pub registries: Vec<String>,
+    /// Lines of the word list already processed.
+    pub line: usize,
+    pub checked: usize,
+    pub skipped: usize,
+    pub free: usize,
+    pub unknown: usize,
+}
+
+impl MineState {
+    pub fn new(wordlist: &Path, constraints: &str, registries: &[&'static Registry]) -> MineState {
+        MineState {
+            wordlist: wordlist.display().to_string(),
+            constraints: constraints.to_string(),
+            registries: registries.iter().map(|r| r.id.to_string()).collect(),
+            ..MineState::default()
+        }
+    }
+
+    /// Load a saved state, or `None` if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Option<MineState>, Box<dyn std::error::Error>> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("{}: {}", path.display(), e).into()),
+        };
+        let state =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Some(state))
+    }
+
+    /// Write the state atomically (temp file, then rename).
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .map_err(|e| format!("{}: {}", tmp.display(), e))?;
+        std::fs::rename(&tmp, path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(())
+    }
+
+    /// True if this state was saved by a run with the same search.
+    pub fn matches(&self, other: &MineState) -> bool {
+        self.wordlist == other.wordlist
+            && self.constraints == other.constraints
+            && self.registries == other.registries
+    }
+}
+
+/// How fast to go: `batch` names are checked concurrently, and batches are
+/// spaced so no more than `rate` names are checked per second.
+#[derive(Debug, Clone, Copy)]
+pub struct Pace {
+    pub rate: f64,
+    pub batch: usize,
+}
+
+/// Stream `wordlist` from `state.line`, check every word that passes
+/// `constraints`, and call `on_batch` after each batch with the updated
+/// state and the results that are free on every registry. The callback
+/// persists the state and writes the results; an error from it stops the run.
+pub async fn mine<F>(
+    wordlist: &Path,
+    constraints: &Constraints,
+    registries: &[&'static Registry],
+    pace: Pace,
+    options: &CheckOptions,
+    state: &mut MineState,
+    mut on_batch: F,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: FnMut(&MineState, &[CheckResult]) -> Result<(), Box<dyn std::error::Error>>,
+{
+    let file =
+        std::fs::File::open(wordlist).map_err(|e| format!("{}: {}", wordlist.display(), e))?;
+    let mut lines = BufReader::new(file).lines().skip(state.line);
+    let batch_size = pace.batch.max(1);
+    let min_interval = if pace.rate > 0.0 {
+        Duration::from_secs_f64(batch_size as f64 / pace.rate)
+    } else {
+        Duration::ZERO
+    };
+
+    loop {
+        let mut batch: Vec<String> = Vec::new();
+        let mut consumed = 0;
+        let mut done = false;
+        while batch.len() < batch_size {
+            let Some(line) = lines.next() else {
+                done = true;
+                break;
+            };
+            let line = line.map_err(|e| format!("{}: {}", wordlist.display(), e))?;
+            consumed += 1;
+            let word = line.trim().to_lowercase();
+            if word.is_empty() || word.starts_with('#') {
+                continue;
+            }
+            if constraints.allows(&word, registries) && !batch.contains(&word) {
+                batch.push(word);
+            } else {
+                state.skipped += 1;
+            }
+        }
+
+        let started = Instant::now();
+        let mut free = Vec::new();
+        if !batch.is_empty() {
+            let results = checker::check_packages_with_options(&batch, registries, options).await;
+            for result in results {
+                state.checked += 1;
+                if result.summary.unknown > 0 {
+                    state.unknown += 1;
+                } else if result.summary.available == result.summary.total {
+                    state.free += 1;
+                    free.push(result);
+                }
+            }
+        }
+        if consumed > 0 {
+            state.line += consumed;
+            on_batch(state, &free)?;
+        }
+
+        if done {
+            return Ok(());
+        }
+        if !batch.is_empty()
+            && let Some(wait) = min_interval.checked_sub(started.elapsed())
+        {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
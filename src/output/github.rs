@@ -0,0 +1,61 @@
+use super::markdown_pivot;
+use crate::registry::Registry;
+use crate::types::{Availability, CheckResult};
+
+/// GitHub Actions workflow commands: `::error` for each taken registry and
+/// `::warning` for each one that couldn't be checked.
+pub fn annotations(results: &[CheckResult]) -> String {
+    let mut out = String::new();
+    for result in results {
+        for pkg in &result.results {
+            let (level, message) = match pkg.available {
+                Availability::Taken => {
+                    let url = pkg
+                        .browse_url
+                        .as_deref()
+                        .map(|u| format!(" ({})", u))
+                        .unwrap_or_default();
+                    (
+                        "error",
+                        format!("{} is taken on {}{}", result.name, pkg.registry_name, url),
+                    )
+                }
+                Availability::Unknown => (
+                    "warning",
+                    format!(
+                        "could not check {} on {}: {}",
+                        result.name,
+                        pkg.registry_name,
+                        pkg.error.as_deref().unwrap_or("unknown error"),
+                    ),
+                ),
+                _ => continue,
+            };
+            out.push_str(&format!(
+                "::{} title={}::{}\n",
+                level,
+                escape_property(&format!("staked: {}", result.name)),
+                escape_data(&message),
+            ));
+        }
+    }
+    out
+}
+
+/// The Markdown written to `$GITHUB_STEP_SUMMARY`.
+pub fn step_summary(results: &[CheckResult], registries: &[&'static Registry]) -> String {
+    format!(
+        "### staked\n\n{}\n✓ available · ✗ taken · = owned by you · ? unknown\n",
+        markdown_pivot(results, registries)
+    )
+}
+
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
@@ -0,0 +1,197 @@
+use crate::registry::Registry;
+use crate::types::{Availability, CheckResult, PackageResult};
+use chrono::{DateTime, Utc};
+
+const STYLE: &str = "
+body { font: 14px/1.4 -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; margin: 2em; color: #1f2328; }
+h1 { font-size: 1.4em; margin-bottom: 0.2em; }
+.meta { color: #59636e; margin-bottom: 1.5em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d1d9e0; padding: 4px 10px; text-align: center; }
+th { background: #f6f8fa; cursor: pointer; user-select: none; }
+th:first-child, td:first-child { text-align: left; font-weight: 600; }
+td a { color: inherit; text-decoration: none; display: block; }
+.available { background: #dafbe1; }
+.taken { background: #ffebe9; }
+.owned_by_you { background: #ddf4ff; }
+.unknown { background: #fff8c5; }
+.legend span { display: inline-block; padding: 1px 8px; margin-right: 6px; border: 1px solid #d1d9e0; }
+details { margin: 0.4em 0; }
+dl { margin: 0.3em 0 0.8em 1.5em; }
+dt { font-weight: 600; }
+";
+
+/// Click a header to sort by that column; numeric columns sort numerically.
+const SCRIPT: &str = "
+document.querySelectorAll('th').forEach(function (th, col) {
+  th.addEventListener('click', function () {
+    var body = th.closest('table').tBodies[0];
+    var asc = th.dataset.sort !== 'asc';
+    th.dataset.sort = asc ? 'asc' : 'desc';
+    var key = function (row) { return row.cells[col].dataset.key || row.cells[col].textContent; };
+    Array.from(body.rows).sort(function (a, b) {
+      var x = key(a), y = key(b), nx = parseFloat(x), ny = parseFloat(y);
+      var cmp = (!isNaN(nx) && !isNaN(ny)) ? nx - ny : x.localeCompare(y);
+      return asc ? cmp : -cmp;
+    }).forEach(function (row) { body.appendChild(row); });
+  });
+});
+";
+
+/// A single offline HTML page: a sortable names × registries table with
+/// colour-coded, linked cells, details for taken packages, and run metadata.
+pub fn render(
+    results: &[CheckResult],
+    registries: &[&'static Registry],
+    generated_at: DateTime<Utc>,
+) -> String {
+    let mut out =
+        String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<title>staked report</title>\n");
+    out.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    out.push_str("<h1>Package name availability</h1>\n");
+    out.push_str(&format!(
+        "<p class=\"meta\">staked {} · generated {} · {} names on {} registries: {}</p>\n",
+        env!("CARGO_PKG_VERSION"),
+        generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        results.len(),
+        registries.len(),
+        escape(
+            &registries
+                .iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    ));
+    out.push_str(
+        "<p class=\"legend\"><span class=\"available\">✓ available</span><span class=\"taken\">✗ taken</span>\
+         <span class=\"owned_by_you\">= owned by you</span><span class=\"unknown\">? unknown</span></p>\n",
+    );
+
+    out.push_str("<table>\n<thead><tr><th>Name</th>");
+    for reg in registries {
+        out.push_str(&format!(
+            "<th title=\"{}\">{}</th>",
+            escape(reg.name),
+            escape(reg.id)
+        ));
+    }
+    out.push_str("<th>Free</th><th>Score</th></tr></thead>\n<tbody>\n");
+    for result in results {
+        out.push_str(&format!("<tr><td>{}</td>", escape(&result.name)));
+        for reg in registries {
+            let pkg = result.results.iter().find(|p| p.registry_id == reg.id);
+            out.push_str(&cell(pkg));
+        }
+        out.push_str(&format!(
+            "<td data-key=\"{}\">{}/{}</td><td>{:.0}</td></tr>\n",
+            result.summary.available,
+            result.summary.available,
+            result.summary.total,
+            result.score.value,
+        ));
+    }
+    out.push_str("</tbody>\n</table>\n");
+
+    let taken: Vec<(&CheckResult, &PackageResult)> = results
+        .iter()
+        .flat_map(|r| r.results.iter().map(move |p| (r, p)))
+        .filter(|(_, p)| p.details.is_some())
+        .collect();
+    if !taken.is_empty() {
+        out.push_str("<h2>Taken packages</h2>\n");
+        for (result, pkg) in taken {
+            out.push_str(&details(result, pkg));
+        }
+    }
+
+    out.push_str(&format!("<script>{}</script>\n</body>\n</html>\n", SCRIPT));
+    out
+}
+
+fn cell(pkg: Option<&PackageResult>) -> String {
+    let Some(pkg) = pkg else {
+        return "<td class=\"unknown\">?</td>".to_string();
+    };
+    let (class, symbol) = match pkg.available {
+        Availability::Available => ("available", "✓"),
+        Availability::Taken => ("taken", "✗"),
+        Availability::OwnedByYou => ("owned_by_you", "="),
+        Availability::Unknown => ("unknown", "?"),
+    };
+    let mut title = pkg.available.to_string();
+    if let Some(ref description) = pkg.details.as_ref().and_then(|d| d.description.clone()) {
+        title.push_str(&format!(": {}", description));
+    }
+    if let Some(ref error) = pkg.error {
+        title.push_str(&format!(": {}", error));
+    }
+    let content = match pkg.browse_url {
+        Some(ref url) => format!("<a href=\"{}\">{}</a>", escape(url), symbol),
+        None => symbol.to_string(),
+    };
+    format!(
+        "<td class=\"{}\" title=\"{}\">{}</td>",
+        class,
+        escape(&title),
+        content
+    )
+}
+
+fn details(result: &CheckResult, pkg: &PackageResult) -> String {
+    let Some(ref details) = pkg.details else {
+        return String::new();
+    };
+    let mut facts: Vec<(&str, String)> = Vec::new();
+    if let Some(ref description) = details.description {
+        facts.push(("Description", escape(description)));
+    }
+    if let Some(ref version) = details.latest_version {
+        facts.push(("Latest version", escape(version)));
+    }
+    if let Some(ref published) = details.last_published {
+        facts.push(("Last published", escape(published)));
+    }
+    if !details.owners.is_empty() {
+        facts.push(("Owners", escape(&details.owners.join(", "))));
+    }
+    if let Some(ref downloads) = details.downloads {
+        facts.push((
+            "Downloads",
+            format!("{} ({})", downloads.count, escape(&downloads.period)),
+        ));
+    }
+    for (label, url) in [
+        ("Repository", &details.repository),
+        ("Homepage", &details.homepage),
+    ] {
+        if let Some(url) = url {
+            facts.push((label, format!("<a href=\"{0}\">{0}</a>", escape(url))));
+        }
+    }
+    if let Some(ref dormancy) = pkg.dormancy {
+        facts.push((
+            "Activity",
+            format!("{} (score {})", dormancy.status, dormancy.score),
+        ));
+    }
+
+    let mut out = format!(
+        "<details><summary>{} on {}</summary>\n<dl>\n",
+        escape(&result.name),
+        escape(&pkg.registry_name)
+    );
+    for (label, value) in facts {
+        out.push_str(&format!("<dt>{}</dt><dd>{}</dd>\n", label, value));
+    }
+    out.push_str("</dl></details>\n");
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,89 @@
+use crate::types::{Availability, CheckResult};
+
+/// One `<testsuite>` per name and one `<testcase>` per registry. Taken is a
+/// failure, unknown is skipped, and available or owned-by-you passes.
+pub fn render(results: &[CheckResult]) -> String {
+    let count = |status: fn(&Availability) -> bool| {
+        results
+            .iter()
+            .flat_map(|r| &r.results)
+            .filter(|p| status(&p.available))
+            .count()
+    };
+    let tests: usize = results.iter().map(|r| r.results.len()).sum();
+    let failures = count(|s| *s == Availability::Taken);
+    let skipped = count(|s| *s == Availability::Unknown);
+    let time: u64 = results.iter().map(|r| r.elapsed_ms).sum();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"staked\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        tests,
+        failures,
+        skipped,
+        seconds(time),
+    ));
+    for result in results {
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+            escape(&result.name),
+            result.summary.total,
+            result.summary.taken,
+            result.summary.unknown,
+            seconds(result.elapsed_ms),
+        ));
+        for pkg in &result.results {
+            let open = format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                escape(&pkg.registry_name),
+                escape(&result.name),
+                seconds(pkg.elapsed_ms),
+            );
+            match pkg.available {
+                Availability::Taken => {
+                    out.push_str(&format!(
+                        "{}>\n      <failure message=\"{} is taken on {}\">{}</failure>\n    </testcase>\n",
+                        open,
+                        escape(&result.name),
+                        escape(&pkg.registry_name),
+                        escape(pkg.browse_url.as_deref().unwrap_or_default()),
+                    ));
+                }
+                Availability::Unknown => {
+                    let reason = pkg.error.as_deref().unwrap_or("could not be checked");
+                    out.push_str(&format!(
+                        "{}>\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                        open,
+                        escape(reason),
+                    ));
+                }
+                Availability::Available | Availability::OwnedByYou => {
+                    out.push_str(&format!("{}/>\n", open));
+                }
+            }
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c if c.is_control() && !matches!(c, '\n' | '\t') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
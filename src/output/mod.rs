@@ -0,0 +1,81 @@
+pub mod github;
+pub mod html;
+pub mod junit;
+pub mod progress;
+pub mod table;
+pub mod template;
+pub mod terminal;
+
+use crate::registry::Registry;
+use crate::types::{Availability, CheckResult};
+
+/// Report formats for the main check, chosen with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Human-readable summary (the default)
+    Text,
+    /// A versioned report (schema version, options, results) as pretty JSON (same as -j)
+    Json,
+    /// JUnit XML: one test suite per name, one test case per registry
+    Junit,
+    /// GitHub Actions annotations, plus a table in $GITHUB_STEP_SUMMARY
+    Github,
+    /// One row per name and registry, comma-separated
+    Csv,
+    /// One row per name and registry, tab-separated
+    Tsv,
+    /// One row per name and registry, as a Markdown table
+    Markdown,
+    /// Names as rows and registries as columns, as a Markdown table
+    MarkdownPivot,
+    /// A self-contained HTML report (use with --output)
+    Html,
+}
+
+/// One-character status used in tables: ✓ available, ✗ taken, = ours, ? unknown.
+pub fn symbol(status: &Availability) -> &'static str {
+    match status {
+        Availability::Available => "✓",
+        Availability::Taken => "✗",
+        Availability::OwnedByYou => "=",
+        Availability::Unknown => "?",
+    }
+}
+
+/// Symbol for `registry_id` in `result`, `?` if it wasn't checked.
+pub fn cell(result: &CheckResult, registry_id: &str) -> &'static str {
+    result
+        .results
+        .iter()
+        .find(|p| p.registry_id == registry_id)
+        .map_or("?", |p| symbol(&p.available))
+}
+
+/// Names as rows, registries as columns, with free counts and scores, as a
+/// Markdown table ready to paste into a PR or doc.
+pub fn markdown_pivot(results: &[CheckResult], registries: &[&'static Registry]) -> String {
+    let mut out = String::from("| Name |");
+    for reg in registries {
+        out.push_str(&format!(" {} |", reg.id));
+    }
+    out.push_str(" Free | Score |\n|------|");
+    for _ in registries {
+        out.push_str(":---:|");
+    }
+    out.push_str("-----:|------:|\n");
+    for result in results {
+        out.push_str(&format!("| {} |", markdown_escape(&result.name)));
+        for reg in registries {
+            out.push_str(&format!(" {} |", cell(result, reg.id)));
+        }
+        out.push_str(&format!(
+            " {}/{} | {:.0} |\n",
+            result.summary.available, result.summary.total, result.score.value
+        ));
+    }
+    out
+}
+
+pub fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
@@ -0,0 +1,108 @@
+use crate::checker::Progress;
+use crate::types::Availability;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// How often the status line is redrawn.
+const REDRAW: Duration = Duration::from_millis(100);
+
+/// Running totals behind the status line.
+#[derive(Debug, Default)]
+pub struct Tally {
+    pub total: usize,
+    pub completed: usize,
+    /// Lookups that came back unknown.
+    pub errors: usize,
+    /// Lookups in flight, by registry.
+    pub in_flight: BTreeMap<&'static str, usize>,
+}
+
+impl Tally {
+    pub fn record(&mut self, event: &Progress) {
+        match event {
+            Progress::Queued { checks } => self.total += checks,
+            Progress::Started { registry_id, .. } => {
+                *self.in_flight.entry(registry_id).or_default() += 1;
+            }
+            Progress::Finished { result, .. } => {
+                self.completed += 1;
+                if result.available == Availability::Unknown {
+                    self.errors += 1;
+                }
+                if let Some(count) = self.in_flight.get_mut(result.registry_id.as_str()) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.in_flight.remove(result.registry_id.as_str());
+                    }
+                }
+            }
+        }
+    }
+
+    /// `[ 120/580]  21%  3.4/s  errors 2%  ETA 2m13s  npm 4, pypi 3`
+    pub fn line(&self, elapsed: Duration) -> String {
+        let digits = self.total.to_string().len();
+        let percent = (self.completed * 100).checked_div(self.total).unwrap_or(0);
+        let mut line = format!(
+            "[{:>digits$}/{}] {:>3}%",
+            self.completed, self.total, percent
+        );
+        let seconds = elapsed.as_secs_f64();
+        if self.completed > 0 && seconds > 0.0 {
+            let rate = self.completed as f64 / seconds;
+            line.push_str(&format!("  {:.1}/s", rate));
+            line.push_str(&format!("  errors {}%", self.errors * 100 / self.completed));
+            let remaining = self.total.saturating_sub(self.completed) as f64 / rate;
+            line.push_str(&format!("  ETA {}", duration(remaining as u64)));
+        }
+        let mut busy: Vec<(&str, usize)> = self.in_flight.iter().map(|(id, n)| (*id, *n)).collect();
+        busy.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        if !busy.is_empty() {
+            let busy: Vec<String> = busy
+                .iter()
+                .map(|(id, count)| format!("{} {}", id, count))
+                .collect();
+            line.push_str(&format!("  {}", busy.join(", ")));
+        }
+        line
+    }
+}
+
+fn duration(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m{:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+/// Draw a status line on stderr from `events` until the sender side is
+/// dropped, then erase it. Only call this when stderr is a terminal.
+pub async fn display(mut events: UnboundedReceiver<Progress>) {
+    let start = Instant::now();
+    let mut tally = Tally::default();
+    let mut ticker = tokio::time::interval(REDRAW);
+    let width = terminal_size::terminal_size_of(std::io::stderr())
+        .map_or(80, |(width, _)| width.0 as usize);
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Some(event) => tally.record(&event),
+                None => break,
+            },
+            _ = ticker.tick() => {
+                let line: String = tally
+                    .line(start.elapsed())
+                    .chars()
+                    .take(width.saturating_sub(1))
+                    .collect();
+                let mut stderr = std::io::stderr().lock();
+                let _ = write!(stderr, "\r\x1b[K{}", line);
+                let _ = stderr.flush();
+            }
+        }
+    }
+    let _ = write!(std::io::stderr(), "\r\x1b[K");
+}
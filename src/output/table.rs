@@ -0,0 +1,101 @@
+use super::markdown_escape;
+use crate::types::{Availability, CheckResult};
+
+const COLUMNS: &[&str] = &[
+    "name",
+    "registry_id",
+    "registry_name",
+    "status",
+    "elapsed_ms",
+    "browse_url",
+    "error",
+];
+
+/// The status as it appears in JSON: `available`, `taken`, `unknown` or
+/// `owned_by_you`.
+pub fn status_key(status: &Availability) -> &'static str {
+    match status {
+        Availability::Available => "available",
+        Availability::Taken => "taken",
+        Availability::Unknown => "unknown",
+        Availability::OwnedByYou => "owned_by_you",
+    }
+}
+
+/// One row per name × registry, in `COLUMNS` order.
+fn rows(results: &[CheckResult]) -> Vec<Vec<String>> {
+    results
+        .iter()
+        .flat_map(|result| {
+            result.results.iter().map(|pkg| {
+                vec![
+                    result.name.clone(),
+                    pkg.registry_id.clone(),
+                    pkg.registry_name.clone(),
+                    status_key(&pkg.available).to_string(),
+                    pkg.elapsed_ms.to_string(),
+                    pkg.browse_url.clone().unwrap_or_default(),
+                    pkg.error.clone().unwrap_or_default(),
+                ]
+            })
+        })
+        .collect()
+}
+
+/// RFC 4180 CSV with a header row.
+pub fn csv(results: &[CheckResult]) -> String {
+    let quote = |field: &str| {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    };
+    let mut out = COLUMNS.join(",") + "\n";
+    for row in rows(results) {
+        let fields: Vec<String> = row.iter().map(|f| quote(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Tab-separated with a header row; tabs and newlines inside fields become
+/// spaces.
+pub fn tsv(results: &[CheckResult]) -> String {
+    let mut out = COLUMNS.join("\t") + "\n";
+    for row in rows(results) {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|f| f.replace(['\t', '\n', '\r'], " "))
+            .collect();
+        out.push_str(&fields.join("\t"));
+        out.push('\n');
+    }
+    out
+}
+
+/// A Markdown table with one row per name × registry.
+pub fn markdown(results: &[CheckResult]) -> String {
+    let mut out = String::from("| Name | Registry | Status | Time (ms) | Link | Error |\n");
+    out.push_str("|------|----------|--------|----------:|------|-------|\n");
+    for result in results {
+        for pkg in &result.results {
+            let link = pkg
+                .browse_url
+                .as_deref()
+                .map(|url| format!("[view]({})", url))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                markdown_escape(&result.name),
+                markdown_escape(&pkg.registry_name),
+                status_key(&pkg.available),
+                pkg.elapsed_ms,
+                link,
+                markdown_escape(pkg.error.as_deref().unwrap_or_default()),
+            ));
+        }
+    }
+    out
+}
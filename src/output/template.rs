@@ -0,0 +1,372 @@
+use crate::types::{CheckResult, PackageResult};
+use chrono::{DateTime, Utc};
+
+/// Fields of the whole report.
+pub const REPORT_FIELDS: &[&str] = &["version", "generated_at", "count"];
+/// Fields of a `CheckResult` and its `Summary` and `Score`.
+pub const RESULT_FIELDS: &[&str] = &[
+    "name",
+    "variant_of",
+    "available",
+    "taken",
+    "unknown",
+    "owned_by_you",
+    "total",
+    "clear",
+    "score",
+    "eligible",
+    "rank",
+    "elapsed_ms",
+];
+/// Fields of a `PackageResult` and its details.
+pub const PACKAGE_FIELDS: &[&str] = &[
+    "registry_id",
+    "registry_name",
+    "status",
+    "url",
+    "browse_url",
+    "http_status",
+    "elapsed_ms",
+    "error",
+    "description",
+    "latest_version",
+    "owners",
+];
+
+/// A parsed template. `{field}` is replaced by a value, `{#results}...{/results}`
+/// repeats once per name and `{#registries}...{/registries}` once per
+/// registry of the enclosing name. `{{` and `}}` are literal braces.
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Field(String),
+    Section(Section, Vec<Node>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Results,
+    Registries,
+}
+
+enum Scope<'a> {
+    Report {
+        count: usize,
+        generated_at: DateTime<Utc>,
+    },
+    Result(&'a CheckResult),
+    Package(&'a PackageResult),
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Template, String> {
+        let mut stack: Vec<(Option<Section>, Vec<Node>)> = vec![(None, Vec::new())];
+        let mut text = String::new();
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => return Err("unmatched '}' (write '}}' for a literal brace)".to_string()),
+                '{' => {
+                    let mut tag = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => tag.push(c),
+                            None => return Err(format!("unclosed '{{{}'", tag)),
+                        }
+                    }
+                    let nodes = &mut stack.last_mut().expect("root is never popped").1;
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+                    let tag = tag.trim();
+                    if let Some(name) = tag.strip_prefix('#') {
+                        stack.push((Some(section(name)?), Vec::new()));
+                    } else if let Some(name) = tag.strip_prefix('/') {
+                        let closing = section(name)?;
+                        let (open, body) = stack.pop().expect("root is never popped");
+                        if open != Some(closing) || stack.is_empty() {
+                            return Err(format!("'{{/{}}}' doesn't close an open section", name));
+                        }
+                        let parent = &mut stack.last_mut().expect("checked above").1;
+                        parent.push(Node::Section(closing, body));
+                    } else {
+                        if !is_field(tag) {
+                            let mut fields =
+                                [REPORT_FIELDS, RESULT_FIELDS, PACKAGE_FIELDS].concat();
+                            fields.sort_unstable();
+                            fields.dedup();
+                            return Err(format!(
+                                "unknown field '{{{}}}' (fields: {})",
+                                tag,
+                                fields.join(", ")
+                            ));
+                        }
+                        nodes.push(Node::Field(tag.to_string()));
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+        if stack.len() > 1 {
+            return Err("unclosed section: add a matching '{/...}'".to_string());
+        }
+        let mut nodes = stack.pop().expect("root is never popped").1;
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok(Template { nodes })
+    }
+
+    /// Render the template once per name × registry, one line each.
+    pub fn render_rows(&self, results: &[CheckResult]) -> Result<String, String> {
+        let report = Scope::Report {
+            count: results.len(),
+            generated_at: Utc::now(),
+        };
+        let mut out = String::new();
+        for result in results {
+            for pkg in &result.results {
+                let scopes = [&report, &Scope::Result(result), &Scope::Package(pkg)];
+                render(&self.nodes, &scopes, &mut out)?;
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Render the template once for the whole report; use `{#results}` to
+    /// loop over names.
+    pub fn render_report(
+        &self,
+        results: &[CheckResult],
+        generated_at: DateTime<Utc>,
+    ) -> Result<String, String> {
+        let report = Scope::Report {
+            count: results.len(),
+            generated_at,
+        };
+        let mut out = String::new();
+        render_sections(&self.nodes, &[&report], results, &mut out)?;
+        Ok(out)
+    }
+}
+
+/// Turn `\t`, `\n` and `\\` into the characters they name, for templates
+/// given on the command line.
+pub fn unescape(template: &str) -> String {
+    let mut out = String::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn section(name: &str) -> Result<Section, String> {
+    match name.trim() {
+        "results" => Ok(Section::Results),
+        "registries" => Ok(Section::Registries),
+        other => Err(format!(
+            "unknown section '{}' (sections: results, registries)",
+            other
+        )),
+    }
+}
+
+fn is_field(name: &str) -> bool {
+    [REPORT_FIELDS, RESULT_FIELDS, PACKAGE_FIELDS]
+        .iter()
+        .any(|fields| fields.contains(&name))
+}
+
+fn render_sections(
+    nodes: &[Node],
+    scopes: &[&Scope],
+    results: &[CheckResult],
+    out: &mut String,
+) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Section(Section::Results, body) => {
+                for result in results {
+                    let scope = Scope::Result(result);
+                    let mut inner = scopes.to_vec();
+                    inner.push(&scope);
+                    render(body, &inner, out)?;
+                }
+            }
+            other => render(std::slice::from_ref(other), scopes, out)?,
+        }
+    }
+    Ok(())
+}
+
+fn render(nodes: &[Node], scopes: &[&Scope], out: &mut String) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Field(name) => out.push_str(&lookup(scopes, name)?),
+            Node::Section(Section::Registries, body) => {
+                let Some(result) = scopes.iter().rev().find_map(|s| match s {
+                    Scope::Result(result) => Some(*result),
+                    _ => None,
+                }) else {
+                    return Err("{#registries} must be inside {#results}".to_string());
+                };
+                for pkg in &result.results {
+                    let scope = Scope::Package(pkg);
+                    let mut inner = scopes.to_vec();
+                    inner.push(&scope);
+                    render(body, &inner, out)?;
+                }
+            }
+            Node::Section(Section::Results, _) => {
+                return Err("{#results} can't be nested".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Look `name` up from the innermost scope outwards.
+fn lookup(scopes: &[&Scope], name: &str) -> Result<String, String> {
+    for scope in scopes.iter().rev() {
+        if let Some(value) = field(scope, name) {
+            return Ok(value);
+        }
+    }
+    Err(format!("'{{{}}}' isn't available here", name))
+}
+
+fn field(scope: &Scope, name: &str) -> Option<String> {
+    let value = match scope {
+        Scope::Report {
+            count,
+            generated_at,
+        } => match name {
+            "version" => env!("CARGO_PKG_VERSION").to_string(),
+            "generated_at" => generated_at.to_rfc3339(),
+            "count" => count.to_string(),
+            _ => return None,
+        },
+        Scope::Result(result) => match name {
+            "name" => result.name.clone(),
+            "variant_of" => result.variant_of.clone().unwrap_or_default(),
+            "available" => result.summary.available.to_string(),
+            "taken" => result.summary.taken.to_string(),
+            "unknown" => result.summary.unknown.to_string(),
+            "owned_by_you" => result.summary.owned_by_you.to_string(),
+            "total" => result.summary.total.to_string(),
+            "clear" => result.summary.clear.to_string(),
+            "score" => format!("{:.0}", result.score.value),
+            "eligible" => result.score.eligible.to_string(),
+            "rank" => result.score.rank.map(|r| r.to_string()).unwrap_or_default(),
+            "elapsed_ms" => result.elapsed_ms.to_string(),
+            _ => return None,
+        },
+        Scope::Package(pkg) => {
+            let details = pkg.details.as_ref();
+            match name {
+                "registry_id" => pkg.registry_id.clone(),
+                "registry_name" => pkg.registry_name.clone(),
+                "status" => super::table::status_key(&pkg.available).to_string(),
+                "url" | "browse_url" => pkg.browse_url.clone().unwrap_or_default(),
+                "http_status" => pkg.http_status.map(|s| s.to_string()).unwrap_or_default(),
+                "elapsed_ms" => pkg.elapsed_ms.to_string(),
+                "error" => pkg.error.clone().unwrap_or_default(),
+                "description" => details
+                    .and_then(|d| d.description.clone())
+                    .unwrap_or_default(),
+                "latest_version" => details
+                    .and_then(|d| d.latest_version.clone())
+                    .unwrap_or_default(),
+                "owners" => details.map(|d| d.owners.join(", ")).unwrap_or_default(),
+                _ => return None,
+            }
+        }
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::{self, CheckOptions};
+
+    fn results(names: &[&str]) -> Vec<CheckResult> {
+        let options = CheckOptions::default();
+        names
+            .iter()
+            .map(|name| checker::assemble(name, Vec::new(), &options, 0))
+            .collect()
+    }
+
+    #[test]
+    fn parse_rejects_bad_templates() {
+        let error = |source: &str| Template::parse(source).unwrap_err();
+        assert!(error("{nope}").starts_with("unknown field '{nope}'"));
+        assert!(error("{name").starts_with("unclosed '{name'"));
+        assert!(error("name}").starts_with("unmatched '}'"));
+        assert!(error("{#results}{name}").starts_with("unclosed section"));
+        assert!(error("{/results}").contains("doesn't close an open section"));
+        assert!(error("{#results}{/registries}").contains("doesn't close an open section"));
+        assert!(error("{#owners}{/owners}").starts_with("unknown section 'owners'"));
+    }
+
+    #[test]
+    fn parse_keeps_literal_braces_and_trims_tags() {
+        let template = Template::parse("{{{ count }}}: {#results}{name},{/results}").unwrap();
+        let out = template
+            .render_report(&results(&["a", "b"]), Utc::now())
+            .unwrap();
+        assert_eq!(out, "{2}: a,b,");
+    }
+
+    #[test]
+    fn fields_resolve_from_the_innermost_scope() {
+        let template = Template::parse("{count} {#results}[{name} {total}]{/results}").unwrap();
+        let out = template
+            .render_report(&results(&["a", "b"]), Utc::now())
+            .unwrap();
+        assert_eq!(out, "2 [a 0][b 0]");
+
+        let template = Template::parse("{name}").unwrap();
+        let error = template.render_report(&results(&["a"]), Utc::now());
+        assert_eq!(error.unwrap_err(), "'{name}' isn't available here");
+    }
+
+    #[test]
+    fn unescape_command_line_templates() {
+        assert_eq!(unescape(r"{name}\t{status}\n"), "{name}\t{status}\n");
+        assert_eq!(unescape(r"a\\b\q\"), r"a\b\q\");
+    }
+}
@@ -0,0 +1,519 @@
+use crate::registry::{self, Registry};
+use crate::types::{Activity, Availability, CheckResult, Dormancy, PackageResult, TakenDetails};
+use std::io::IsTerminal;
+
+/// When to colour human-readable output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Colour when stdout is a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// How text output is decorated: ANSI colours, OSC-8 hyperlinks and the
+/// width to fit into.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Style {
+    pub color: bool,
+    pub links: bool,
+    pub width: Option<usize>,
+}
+
+impl Style {
+    /// Decide from `choice`, whether stdout is a terminal, `NO_COLOR` and
+    /// `TERM=dumb`. Hyperlinks are only emitted to a terminal.
+    pub fn detect(choice: ColorChoice) -> Style {
+        let tty = std::io::stdout().is_terminal();
+        let dumb = std::env::var("TERM").is_ok_and(|t| t == "dumb");
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        let color = match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => tty && !dumb && !no_color,
+        };
+        Style {
+            color,
+            links: tty && !dumb,
+            width: terminal_width(),
+        }
+    }
+
+    fn paint(&self, text: &str, code: &str) -> String {
+        if self.color && !text.is_empty() {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn bold(&self, text: &str) -> String {
+        self.paint(text, "1")
+    }
+
+    fn dim(&self, text: &str) -> String {
+        self.paint(text, "2")
+    }
+
+    /// `text` in the colour for `status`.
+    fn status(&self, text: &str, status: &Availability) -> String {
+        let code = match status {
+            Availability::Available => "32",
+            Availability::Taken => "31",
+            Availability::Unknown => "33",
+            Availability::OwnedByYou => "36",
+        };
+        self.paint(text, code)
+    }
+
+    /// `text` as an OSC-8 hyperlink to `url`, when links are on.
+    fn link(&self, text: &str, url: Option<&str>) -> String {
+        match url {
+            Some(url) if self.links => format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text),
+            _ => text.to_string(),
+        }
+    }
+}
+
+/// Width of the terminal stdout is attached to, or `$COLUMNS`; `None` when
+/// neither is known (e.g. output is piped), in which case nothing is
+/// narrowed.
+pub fn terminal_width() -> Option<usize> {
+    terminal_size::terminal_size()
+        .map(|(width, _)| width.0 as usize)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+}
+
+/// Coarse ecosystem for grouping rows: the part of the registry's ecosystem
+/// label before any " / " or " (", so "Python / Data Science" groups with
+/// "Python".
+pub fn ecosystem_group(registry: &Registry) -> &'static str {
+    let ecosystem = registry.ecosystem;
+    let end = [" / ", " ("]
+        .iter()
+        .filter_map(|sep| ecosystem.find(sep))
+        .min()
+        .unwrap_or(ecosystem.len());
+    &ecosystem[..end]
+}
+
+fn group_of(registry_id: &str) -> &'static str {
+    registry::all_registries()
+        .iter()
+        .find(|r| r.id == registry_id)
+        .map_or("", ecosystem_group)
+}
+
+/// Registries grouped by ecosystem, groups in order of first appearance.
+fn grouped(registries: &[&'static Registry]) -> Vec<(&'static str, Vec<&'static Registry>)> {
+    let mut groups: Vec<(&str, Vec<&'static Registry>)> = Vec::new();
+    for &reg in registries {
+        let group = ecosystem_group(reg);
+        match groups.iter_mut().find(|(name, _)| *name == group) {
+            Some((_, regs)) => regs.push(reg),
+            None => groups.push((group, vec![reg])),
+        }
+    }
+    groups
+}
+
+/// The default block for one name: a summary line, then either the
+/// available/taken lists or, with `verbose`, one line per registry.
+pub fn result_block(result: &CheckResult, verbose: bool, style: &Style) -> String {
+    let mut out = format!("{}:\n", style.bold(&result.name));
+    let ours = if result.summary.owned_by_you > 0 {
+        format!(", {} owned by you", result.summary.owned_by_you)
+    } else {
+        String::new()
+    };
+    out.push_str(&format!(
+        "  {} available, {} taken, {} unknown{}, score {:.0} {}\n",
+        result.summary.available,
+        result.summary.taken,
+        result.summary.unknown,
+        ours,
+        result.score.value,
+        style.dim(&format!("({}ms)", result.elapsed_ms)),
+    ));
+    if !result.score.required_blocked.is_empty() {
+        out.push_str(&format!(
+            "  {} {}\n",
+            style.status("required but not free:", &Availability::Taken),
+            result.score.required_blocked.join(", ")
+        ));
+    }
+    if let Some(ref owner) = result.dominant_owner {
+        out.push_str(&format!("  owner: {}\n", owner));
+    }
+
+    if verbose {
+        out.push_str(&registry_lines(&result.results, style));
+    } else {
+        let names = |status: Availability| -> Vec<String> {
+            result
+                .results
+                .iter()
+                .filter(|r| r.available == status)
+                .map(|r| style.link(&r.registry_name, r.browse_url.as_deref()))
+                .collect()
+        };
+        for (label, status) in [
+            ("available:", Availability::Available),
+            ("owned by you:", Availability::OwnedByYou),
+            ("taken:", Availability::Taken),
+        ] {
+            let list = names(status.clone());
+            if !list.is_empty() {
+                out.push_str(&format!(
+                    "  {} {}\n",
+                    style.status(label, &status),
+                    list.join(", ")
+                ));
+            }
+        }
+
+        let dormant: Vec<String> = result
+            .results
+            .iter()
+            .filter_map(|r| {
+                r.dormancy
+                    .as_ref()
+                    .filter(|d| d.status == Activity::Dormant)
+                    .map(|d| format!("{} ({})", r.registry_name, d.score))
+            })
+            .collect();
+        if !dormant.is_empty() {
+            out.push_str(&format!("  dormant: {}\n", dormant.join(", ")));
+        }
+    }
+    out.push('\n');
+    out
+}
+
+/// One line per registry, grouped by ecosystem, each followed by whatever
+/// details and dormancy information was fetched.
+fn registry_lines(results: &[PackageResult], style: &Style) -> String {
+    let name_width = results
+        .iter()
+        .map(|p| p.registry_name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut order: Vec<&PackageResult> = results.iter().collect();
+    let groups: Vec<&str> = {
+        let mut seen: Vec<&str> = Vec::new();
+        for pkg in &order {
+            let group = group_of(&pkg.registry_id);
+            if !seen.contains(&group) {
+                seen.push(group);
+            }
+        }
+        seen
+    };
+    order.sort_by_key(|p| {
+        let group = group_of(&p.registry_id);
+        groups.iter().position(|g| *g == group)
+    });
+
+    let mut out = String::new();
+    for pkg in order {
+        let marker = match pkg.available {
+            Availability::Available => "[+]",
+            Availability::Taken => "[-]",
+            Availability::Unknown => "[?]",
+            Availability::OwnedByYou => "[=]",
+        };
+        let name = format!("{:<name_width$}", pkg.registry_name);
+        out.push_str(&format!(
+            "  {} {} {} {} {}\n",
+            style.status(marker, &pkg.available),
+            style.link(&name, pkg.browse_url.as_deref()),
+            style.status(
+                &format!("{:<12}", pkg.available.to_string()),
+                &pkg.available
+            ),
+            style.dim(&format!("{:>7}", format!("{}ms", pkg.elapsed_ms))),
+            style.dim(group_of(&pkg.registry_id)),
+        ));
+        if let Some(ref details) = pkg.details {
+            out.push_str(&details_lines(details, style));
+        }
+        if let Some(ref dormancy) = pkg.dormancy {
+            out.push_str(&dormancy_lines(dormancy, style));
+        }
+    }
+    out
+}
+
+fn details_lines(details: &TakenDetails, style: &Style) -> String {
+    let mut out = String::new();
+    if let Some(ref description) = details.description {
+        out.push_str(&format!("        {}\n", description));
+    }
+    let mut facts = Vec::new();
+    if let Some(ref version) = details.latest_version {
+        facts.push(format!("v{}", version));
+    }
+    if let Some(ref published) = details.last_published {
+        facts.push(format!("published {}", published));
+    }
+    if let Some(ref downloads) = details.downloads {
+        facts.push(format!(
+            "{} downloads ({})",
+            downloads.count, downloads.period
+        ));
+    }
+    if !facts.is_empty() {
+        out.push_str(&format!("        {}\n", style.dim(&facts.join(", "))));
+    }
+    if !details.owners.is_empty() {
+        out.push_str(&format!("        owners: {}\n", details.owners.join(", ")));
+    }
+    if let Some(ref repository) = details.repository {
+        out.push_str(&format!(
+            "        repo: {}\n",
+            style.link(repository, Some(repository))
+        ));
+    } else if let Some(ref homepage) = details.homepage {
+        out.push_str(&format!(
+            "        homepage: {}\n",
+            style.link(homepage, Some(homepage))
+        ));
+    }
+    out
+}
+
+fn dormancy_lines(dormancy: &Dormancy, style: &Style) -> String {
+    let status = dormancy.status.to_string();
+    let status = if dormancy.status == Activity::Dormant {
+        style.paint(&status, "33")
+    } else {
+        status
+    };
+    let mut out = if dormancy.reasons.is_empty() {
+        format!("        {} (score {})\n", status, dormancy.score)
+    } else {
+        format!(
+            "        {} (score {}): {}\n",
+            status,
+            dormancy.score,
+            dormancy.reasons.join("; "),
+        )
+    };
+    if dormancy.status == Activity::Dormant
+        && let Some(ref policy) = dormancy.transfer_policy
+    {
+        out.push_str(&format!(
+            "        transfer: {} <{}>\n",
+            policy.name,
+            style.link(&policy.url, Some(&policy.url))
+        ));
+    }
+    out
+}
+
+/// One line per name: a verdict mark, free count, score and the registries
+/// that aren't free, cut to the terminal width.
+pub fn compact(results: &[CheckResult], style: &Style) -> String {
+    let name_width = results
+        .iter()
+        .map(|r| r.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for result in results {
+        let (mark, status) = if result.summary.clear {
+            ("✓", Availability::Available)
+        } else if result.summary.taken > 0 {
+            ("✗", Availability::Taken)
+        } else {
+            ("?", Availability::Unknown)
+        };
+        let prefix = format!(
+            "  {} {:<name_width$}  {:>5}  score {:>3.0}",
+            mark,
+            result.name,
+            format!("{}/{}", result.summary.available, result.summary.total),
+            result.score.value,
+        );
+        let mut blocked: Vec<String> = Vec::new();
+        for (label, status) in [
+            ("taken", Availability::Taken),
+            ("unknown", Availability::Unknown),
+        ] {
+            let ids: Vec<&str> = result
+                .results
+                .iter()
+                .filter(|p| p.available == status)
+                .map(|p| p.registry_id.as_str())
+                .collect();
+            if !ids.is_empty() {
+                blocked.push(format!("{}: {}", label, ids.join(", ")));
+            }
+        }
+        let mut tail = if blocked.is_empty() {
+            String::new()
+        } else {
+            format!("  {}", blocked.join("; "))
+        };
+        if let Some(width) = style.width {
+            let room = width.saturating_sub(prefix.chars().count());
+            if tail.chars().count() > room {
+                tail = tail
+                    .chars()
+                    .take(room.saturating_sub(1))
+                    .collect::<String>()
+                    + "…";
+            }
+        }
+        let prefix = prefix.replacen(mark, &style.status(mark, &status), 1);
+        out.push_str(&format!("{}{}\n", prefix, style.dim(&tail)));
+    }
+    out
+}
+
+fn cell(result: &CheckResult, registry_id: &str, width: usize, style: &Style) -> String {
+    let text = format!("{:^width$}", super::cell(result, registry_id));
+    match result.results.iter().find(|p| p.registry_id == registry_id) {
+        Some(pkg) => style.link(
+            &style.status(&text, &pkg.available),
+            pkg.browse_url.as_deref(),
+        ),
+        None => text,
+    }
+}
+
+/// One row per name, one column per registry, with the number of
+/// registries each name is free on and its score.
+pub fn name_rows(
+    heading: &str,
+    results: &[&CheckResult],
+    registries: &[&'static Registry],
+    style: &Style,
+) -> String {
+    let name_width = results
+        .iter()
+        .map(|r| r.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(heading.len());
+    let widths: Vec<usize> = registries.iter().map(|r| r.id.len().max(3)).collect();
+
+    let mut header = format!("  {:<name_width$}", heading);
+    for (reg, width) in registries.iter().zip(&widths) {
+        header.push_str(&format!("  {:^width$}", reg.id));
+    }
+    header.push_str("  FREE  SCORE");
+    let mut out = style.bold(&header) + "\n";
+    for result in results {
+        out.push_str(&format!("  {:<name_width$}", result.name));
+        for (reg, width) in registries.iter().zip(&widths) {
+            out.push_str(&format!("  {}", cell(result, reg.id, *width, style)));
+        }
+        let free = format!("{}/{}", result.summary.available, result.summary.total);
+        out.push_str(&format!("  {:<4}  {:>5.0}\n", free, result.score.value));
+    }
+    out.push('\n');
+    out
+}
+
+/// Names as columns, registries as rows grouped by ecosystem, with totals
+/// and scores at the bottom. Falls back to one row per name when that
+/// would be wider than the terminal.
+pub fn matrix(results: &[CheckResult], registries: &[&'static Registry], style: &Style) -> String {
+    let groups = grouped(registries);
+    let group_width = groups
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(9);
+    let reg_width = registries
+        .iter()
+        .map(|r| r.id.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let widths: Vec<usize> = results
+        .iter()
+        .map(|r| r.name.chars().count().max(5))
+        .collect();
+    let total_width = 2 + group_width + 2 + reg_width + widths.iter().map(|w| w + 2).sum::<usize>();
+    if style.width.is_some_and(|width| total_width > width) {
+        let rows: Vec<&CheckResult> = results.iter().collect();
+        return name_rows("NAME", &rows, registries, style);
+    }
+
+    let mut header = format!(
+        "  {:<group_width$}  {:<reg_width$}",
+        "ECOSYSTEM", "REGISTRY"
+    );
+    for (result, width) in results.iter().zip(&widths) {
+        header.push_str(&format!("  {:^width$}", result.name));
+    }
+    let mut out = style.bold(&header) + "\n";
+    for (group, regs) in &groups {
+        for (i, reg) in regs.iter().enumerate() {
+            let label = format!("{:<group_width$}", if i == 0 { *group } else { "" });
+            out.push_str(&format!("  {}  {:<reg_width$}", style.dim(&label), reg.id));
+            for (result, width) in results.iter().zip(&widths) {
+                out.push_str(&format!("  {}", cell(result, reg.id, *width, style)));
+            }
+            out.push('\n');
+        }
+    }
+    out.push_str(&format!("  {:<group_width$}  {:<reg_width$}", "", "FREE"));
+    for (result, width) in results.iter().zip(&widths) {
+        let free = format!("{}/{}", result.summary.available, result.summary.total);
+        out.push_str(&format!("  {:^width$}", free));
+    }
+    out.push('\n');
+    out.push_str(&format!("  {:<group_width$}  {:<reg_width$}", "", "SCORE"));
+    for (result, width) in results.iter().zip(&widths) {
+        out.push_str(&format!(
+            "  {:^width$}",
+            format!("{:.0}", result.score.value)
+        ));
+    }
+    out.push_str("\n\n");
+    out
+}
+
+/// The multi-name comparison, best candidate first.
+pub fn ranking(results: &[CheckResult], order: &[usize], style: &Style) -> String {
+    let name_width = results
+        .iter()
+        .map(|r| r.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(24);
+    let mut out = style.bold("Ranking:") + "\n";
+    for &i in order {
+        let result = &results[i];
+        let blocked = if result.score.eligible {
+            String::new()
+        } else {
+            style.status(
+                &format!("  (required: {})", result.score.required_blocked.join(", ")),
+                &Availability::Taken,
+            )
+        };
+        out.push_str(&format!(
+            "  {:>2}. {:<name_width$} score {:>3.0}  {:>2}/{:<2} available{}\n",
+            result.score.rank.unwrap_or_default(),
+            result.name,
+            result.score.value,
+            result.summary.available,
+            result.summary.total,
+            blocked,
+        ));
+    }
+    match crate::score::best(results) {
+        Some(best) => out.push_str(&format!(
+            "Best: {}\n",
+            style.status(&best.name, &Availability::Available)
+        )),
+        None => out.push_str("No name is free on every required registry.\n"),
+    }
+    out
+}
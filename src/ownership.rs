@@ -0,0 +1,88 @@
+use crate::types::{Availability, OwnerGroup, PackageResult};
+
+/// Code hosts where the first two path segments name the project.
+const FORGES: &[&str] = &[
+    "github.com",
+    "gitlab.com",
+    "bitbucket.org",
+    "codeberg.org",
+    "sr.ht",
+    "git.sr.ht",
+];
+
+/// Hosts that serve many unrelated projects under paths we don't parse, so
+/// matching on them alone says nothing about ownership.
+const SHARED_HOSTS: &[&str] = &[
+    "docs.rs",
+    "crates.io",
+    "npmjs.com",
+    "pypi.org",
+    "rubygems.org",
+    "hex.pm",
+    "hexdocs.pm",
+    "pub.dev",
+    "nuget.org",
+    "hub.docker.com",
+    "cocoapods.org",
+];
+
+/// Reduce a repository or homepage URL to a stable project identity:
+/// `host/owner/repo` for code forges, otherwise the bare host.
+pub fn project_key(url: &str) -> Option<String> {
+    let url = url.trim().to_lowercase();
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(&url);
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    let mut segments = rest.split('/').filter(|s| !s.is_empty());
+    let host = segments.next()?.trim_start_matches("www.");
+    if host.is_empty() || !host.contains('.') {
+        return None;
+    }
+    if FORGES.contains(&host) {
+        let owner = segments.next()?;
+        let repo = segments.next()?;
+        let repo = repo.strip_suffix(".git").unwrap_or(repo);
+        return Some(format!("{host}/{owner}/{repo}"));
+    }
+    if SHARED_HOSTS.contains(&host) {
+        return None;
+    }
+    Some(host.to_string())
+}
+
+/// Group taken results by the project their repository (or, failing that,
+/// homepage) points at. Largest groups come first.
+pub fn group_by_owner(results: &[PackageResult]) -> Vec<OwnerGroup> {
+    let mut groups: Vec<OwnerGroup> = Vec::new();
+    for result in results
+        .iter()
+        .filter(|r| r.available == Availability::Taken)
+    {
+        let Some(project) = result.details.as_ref().and_then(|d| {
+            d.repository
+                .as_deref()
+                .and_then(project_key)
+                .or_else(|| d.homepage.as_deref().and_then(project_key))
+        }) else {
+            continue;
+        };
+        match groups.iter_mut().find(|g| g.project == project) {
+            Some(group) => group.registries.push(result.registry_id.clone()),
+            None => groups.push(OwnerGroup {
+                project,
+                registries: vec![result.registry_id.clone()],
+            }),
+        }
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.registries.len()));
+    groups
+}
+
+/// The owner holding the name on more registries than anyone else, if one
+/// project holds it on at least two.
+pub fn dominant_owner(groups: &[OwnerGroup]) -> Option<OwnerGroup> {
+    let first = groups.first()?;
+    let tied = groups
+        .get(1)
+        .is_some_and(|second| second.registries.len() == first.registries.len());
+    (first.registries.len() >= 2 && !tied).then(|| first.clone())
+}
@@ -0,0 +1,71 @@
+use crate::types::{Availability, CheckResult};
+use serde::{Deserialize, Serialize};
+
+/// Every name is clear under the policy.
+pub const EXIT_CLEAR: i32 = 0;
+/// A required registry is taken (or, with `--fail-on taken`, any is).
+pub const EXIT_TAKEN: i32 = 1;
+/// A registry the policy cares about could not be checked.
+pub const EXIT_INDETERMINATE: i32 = 2;
+/// Bad arguments, config or input files.
+pub const EXIT_USAGE: i32 = 3;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum FailOn {
+    /// Fail if the name is taken on any checked registry
+    Taken,
+    /// Fail if any checked registry could not be checked
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verdict {
+    pub exit_code: i32,
+    /// One line per violation, e.g. `foo: taken on npm (required)`.
+    pub reasons: Vec<String>,
+}
+
+/// Decide the exit code from check results. Required registries come from
+/// each result's `score.required_blocked`; `fail_on` widens the check to
+/// every registry. A taken violation outranks an indeterminate one.
+pub fn evaluate(results: &[CheckResult], fail_on: &[FailOn]) -> Verdict {
+    let mut taken = Vec::new();
+    let mut unknown = Vec::new();
+    for result in results {
+        for pkg in &result.results {
+            let required = result.score.required_blocked.contains(&pkg.registry_id);
+            let suffix = if required { " (required)" } else { "" };
+            match pkg.available {
+                Availability::Taken if required || fail_on.contains(&FailOn::Taken) => {
+                    taken.push(format!(
+                        "{}: taken on {}{}",
+                        result.name, pkg.registry_id, suffix
+                    ));
+                }
+                Availability::Unknown if required || fail_on.contains(&FailOn::Unknown) => {
+                    unknown.push(format!(
+                        "{}: could not check {}{}",
+                        result.name, pkg.registry_id, suffix
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let exit_code = if !taken.is_empty() {
+        EXIT_TAKEN
+    } else if !unknown.is_empty() {
+        EXIT_INDETERMINATE
+    } else {
+        EXIT_CLEAR
+    };
+    taken.extend(unknown);
+    Verdict {
+        exit_code,
+        reasons: taken,
+    }
+}
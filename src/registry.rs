@@ -8,6 +8,23 @@ pub enum AvailableSignal {
     XmlNoEntry,
 }
 
+/// Shape of a registry's check response, used to pull metadata out of it
+/// (and any follow-up endpoints) when a name is taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataFormat {
+    None,
+    Npm,
+    PyPI,
+    Crates,
+    RubyGems,
+    NuGet,
+    Hex,
+    Pub,
+    Homebrew,
+    DockerHub,
+    CocoaPods,
+}
+
 pub struct Registry {
     pub id: &'static str,
     pub name: &'static str,
@@ -16,6 +33,7 @@ pub struct Registry {
     pub check_url_template: &'static str,
     pub browse_url_template: &'static str,
     pub signal: AvailableSignal,
+    pub metadata: MetadataFormat,
     pub headers: &'static [(&'static str, &'static str)],
     pub popular: bool,
 }
@@ -30,6 +48,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://registry.npmjs.org/{name}",
         browse_url_template: "https://www.npmjs.com/package/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::Npm,
         headers: &[],
         popular: true,
     },
@@ -41,6 +60,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://pypi.org/pypi/{name}/json",
         browse_url_template: "https://pypi.org/project/{name}/",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::PyPI,
         headers: &[],
         popular: true,
     },
@@ -52,6 +72,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://crates.io/api/v1/crates/{name}",
         browse_url_template: "https://crates.io/crates/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::Crates,
         headers: &[],
         popular: true,
     },
@@ -63,6 +84,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://rubygems.org/api/v1/gems/{name}.json",
         browse_url_template: "https://rubygems.org/gems/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::RubyGems,
         headers: &[],
         popular: true,
     },
@@ -74,6 +96,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://api.nuget.org/v3-flatcontainer/{name}/index.json",
         browse_url_template: "https://www.nuget.org/packages/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::NuGet,
         headers: &[],
         popular: true,
     },
@@ -85,6 +108,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://hex.pm/api/packages/{name}",
         browse_url_template: "https://hex.pm/packages/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::Hex,
         headers: &[],
         popular: true,
     },
@@ -96,6 +120,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://pub.dev/api/packages/{name}",
         browse_url_template: "https://pub.dev/packages/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::Pub,
         headers: &[],
         popular: true,
     },
@@ -107,6 +132,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://formulae.brew.sh/api/formula/{name}.json",
         browse_url_template: "https://formulae.brew.sh/formula/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::Homebrew,
         headers: &[],
         popular: true,
     },
@@ -118,6 +144,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://hub.docker.com/v2/repositories/library/{name}/",
         browse_url_template: "https://hub.docker.com/_/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::DockerHub,
         headers: &[],
         popular: true,
     },
@@ -129,6 +156,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://trunk.cocoapods.org/api/v1/pods/{name}",
         browse_url_template: "https://cocoapods.org/pods/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::CocoaPods,
         headers: &[],
         popular: true,
     },
@@ -141,6 +169,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://formulae.brew.sh/api/cask/{name}.json",
         browse_url_template: "https://formulae.brew.sh/cask/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -152,6 +181,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://hackage.haskell.org/package/{name}/preferred",
         browse_url_template: "https://hackage.haskell.org/package/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -163,6 +193,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://opam.ocaml.org/packages/{name}/",
         browse_url_template: "https://opam.ocaml.org/packages/{name}/",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -174,6 +205,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://fastapi.metacpan.org/v1/distribution/{name}",
         browse_url_template: "https://metacpan.org/dist/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -185,6 +217,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://luarocks.org/api/1/{name}/rockspec.json",
         browse_url_template: "https://luarocks.org/search?q={name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -196,6 +229,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://nimble.directory/api/packages/{name}",
         browse_url_template: "https://nimble.directory/pkg/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -207,6 +241,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://code.dlang.org/api/packages/{name}",
         browse_url_template: "https://code.dlang.org/packages/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -218,6 +253,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://crandb.r-pkg.org/{name}",
         browse_url_template: "https://cran.r-project.org/package={name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -229,6 +265,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://juliahub.com/ui/Packages/General/{name}",
         browse_url_template: "https://juliahub.com/ui/Packages/General/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -240,6 +277,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://api.anaconda.org/package/conda-forge/{name}",
         browse_url_template: "https://anaconda.org/conda-forge/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -251,6 +289,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://vcpkg.io/en/package/{name}",
         browse_url_template: "https://vcpkg.io/en/package/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -262,6 +301,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://api.snapcraft.io/v2/snaps/info/{name}",
         browse_url_template: "https://snapcraft.io/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[("Snap-Device-Series", "16")],
         popular: false,
     },
@@ -273,6 +313,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://apiland.deno.dev/v2/modules/{name}",
         browse_url_template: "https://deno.land/x/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -284,6 +325,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://shardbox.org/shards/{name}",
         browse_url_template: "https://shardbox.org/shards/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -295,6 +337,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://vpm.vlang.io/packages/{name}",
         browse_url_template: "https://vpm.vlang.io/packages/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -306,6 +349,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://ctan.org/json/2.0/pkg/{name}",
         browse_url_template: "https://ctan.org/pkg/{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -317,6 +361,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://pursuit.purescript.org/packages/purescript-{name}",
         browse_url_template: "https://pursuit.purescript.org/packages/purescript-{name}",
         signal: AvailableSignal::Http404,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -328,6 +373,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://api.wordpress.org/themes/info/1.2/?action=theme_information&slug={name}",
         browse_url_template: "https://wordpress.org/themes/{name}/",
         signal: AvailableSignal::NullOrError,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -339,6 +385,7 @@ pub const REGISTRIES: [Registry; 29] = [
         check_url_template: "https://community.chocolatey.org/api/v2/Packages()?%24filter=Id%20eq%20%27{name}%27&%24top=1",
         browse_url_template: "https://community.chocolatey.org/packages/{name}",
         signal: AvailableSignal::XmlNoEntry,
+        metadata: MetadataFormat::None,
         headers: &[],
         popular: false,
     },
@@ -379,6 +426,34 @@ pub fn check_url(registry: &Registry, name: &str) -> String {
     registry.check_url_template.replace("{name}", &name_for_url)
 }
 
+/// The form `registry` compares names in: two spellings that normalize to
+/// the same string are the same package there. PyPI folds case and runs of
+/// `-`, `_` and `.` (PEP 503), crates.io folds case and treats `_` as `-`,
+/// and the registries listed below are case-insensitive.
+pub fn normalize(registry: &Registry, name: &str) -> String {
+    match registry.id {
+        "pypi" => {
+            let mut out = String::new();
+            for c in name.to_lowercase().chars() {
+                if matches!(c, '-' | '_' | '.') {
+                    if !out.ends_with('-') {
+                        out.push('-');
+                    }
+                } else {
+                    out.push(c);
+                }
+            }
+            out
+        }
+        "crates" => name.to_lowercase().replace('_', "-"),
+        "npm" | "nuget" | "homebrew" | "homebrew_cask" | "docker_hub" | "cocoapods" | "conda"
+        | "snapcraft" | "luarocks" | "chocolatey" | "wordpress_themes" | "vcpkg" => {
+            name.to_lowercase()
+        }
+        _ => name.to_string(),
+    }
+}
+
 pub fn browse_url(registry: &Registry, name: &str) -> String {
     registry.browse_url_template.replace("{name}", name)
 }
@@ -391,3 +466,96 @@ pub fn registry_info(registry: &Registry) -> RegistryInfo {
         languages: registry.languages.iter().map(|l| l.to_string()).collect(),
     }
 }
+
+/// Whether `name` is a legal package name on `registry`. These are the
+/// registries' published naming rules, simplified to the parts that matter
+/// for generated candidates (character set, case, first character, length).
+pub fn is_valid_name(registry: &Registry, name: &str) -> bool {
+    let first = match name.chars().next() {
+        Some(c) => c,
+        None => return false,
+    };
+    let last = name.chars().last().unwrap_or(first);
+    let all = |f: &dyn Fn(char) -> bool| name.chars().all(f);
+    match registry.id {
+        "npm" => {
+            name.len() <= 214
+                && first != '.'
+                && first != '_'
+                && all(&|c| lower_alnum(c) || matches!(c, '-' | '.' | '_' | '~'))
+        }
+        "pypi" => {
+            first.is_ascii_alphanumeric()
+                && last.is_ascii_alphanumeric()
+                && all(&|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+        }
+        "crates" => {
+            name.len() <= 64
+                && first.is_ascii_alphabetic()
+                && all(&|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        }
+        "hex" => first.is_ascii_lowercase() && all(&|c| lower_alnum(c) || c == '_'),
+        "pub" => {
+            (first.is_ascii_lowercase() || first == '_') && all(&|c| lower_alnum(c) || c == '_')
+        }
+        "docker_hub" => {
+            lower_alnum(first)
+                && lower_alnum(last)
+                && all(&|c| lower_alnum(c) || matches!(c, '-' | '.' | '_'))
+                && !name.contains("..")
+                && !name.contains("--")
+        }
+        "homebrew" | "homebrew_cask" => {
+            lower_alnum(first)
+                && all(&|c| lower_alnum(c) || matches!(c, '-' | '.' | '_' | '+' | '@'))
+        }
+        "hackage" => {
+            all(&|c| c.is_ascii_alphanumeric() || c == '-')
+                && name
+                    .split('-')
+                    .all(|part| part.chars().any(|c| c.is_ascii_alphabetic()))
+        }
+        "purescript" => {
+            first.is_ascii_alphabetic() && all(&|c| c.is_ascii_alphanumeric() || c == '-')
+        }
+        "cran" => {
+            first.is_ascii_alphabetic()
+                && last.is_ascii_alphanumeric()
+                && all(&|c| c.is_ascii_alphanumeric() || c == '.')
+        }
+        "nimble" | "julia" => first.is_ascii_alphabetic() && all(&|c| c.is_ascii_alphanumeric()),
+        _ => {
+            first.is_ascii_alphanumeric()
+                && all(&|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+        }
+    }
+}
+
+fn lower_alnum(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn by_id(id: &str) -> &'static Registry {
+        registries_by_ids(&[id.to_string()])[0]
+    }
+
+    #[test]
+    fn normalize_pypi_follows_pep_503() {
+        let pypi = by_id("pypi");
+        assert_eq!(normalize(pypi, "Foo.Bar"), "foo-bar");
+        assert_eq!(normalize(pypi, "foo__bar"), "foo-bar");
+        assert_eq!(normalize(pypi, "foo-_.-bar"), "foo-bar");
+        assert_eq!(normalize(pypi, "FOO_bar"), normalize(pypi, "foo-bar"));
+    }
+
+    #[test]
+    fn normalize_per_registry() {
+        assert_eq!(normalize(by_id("crates"), "Foo_Bar"), "foo-bar");
+        assert_eq!(normalize(by_id("npm"), "Foo_Bar"), "foo_bar");
+        assert_eq!(normalize(by_id("rubygems"), "Foo_Bar"), "Foo_Bar");
+    }
+}
@@ -0,0 +1,138 @@
+use crate::config::ScoringConfig;
+use crate::types::{Availability, CheckResult, PackageResult, Score};
+
+/// Built-in weights; registries not listed weigh 1.0. The big three
+/// language registries are where a name collision hurts most.
+pub const DEFAULT_WEIGHTS: &[(&str, f64)] = &[
+    ("npm", 3.0),
+    ("pypi", 3.0),
+    ("crates", 3.0),
+    ("rubygems", 1.5),
+    ("nuget", 1.5),
+    ("homebrew", 1.5),
+    ("docker_hub", 1.5),
+];
+
+/// The weight of `registry_id`: the config's override if there is one,
+/// otherwise the built-in default.
+pub fn weight(registry_id: &str, scoring: &ScoringConfig) -> f64 {
+    if let Some(&weight) = scoring.weights.get(registry_id) {
+        return weight.max(0.0);
+    }
+    DEFAULT_WEIGHTS
+        .iter()
+        .find(|(id, _)| *id == registry_id)
+        .map_or(1.0, |&(_, weight)| weight)
+}
+
+/// Score one name's per-registry results. Available and owned-by-you count
+/// as free; taken and unknown count against it.
+pub fn score(results: &[PackageResult], scoring: &ScoringConfig) -> Score {
+    let mut free = 0.0;
+    let mut total = 0.0;
+    let mut required_blocked = Vec::new();
+    for pkg in results {
+        let is_free = matches!(
+            pkg.available,
+            Availability::Available | Availability::OwnedByYou
+        );
+        let weight = weight(&pkg.registry_id, scoring);
+        total += weight;
+        if is_free {
+            free += weight;
+        } else if scoring.required.contains(&pkg.registry_id) {
+            required_blocked.push(pkg.registry_id.clone());
+        }
+    }
+    let value = if total > 0.0 {
+        (free / total * 1000.0).round() / 10.0
+    } else {
+        0.0
+    };
+    Score {
+        value,
+        eligible: required_blocked.is_empty(),
+        required_blocked,
+        rank: None,
+    }
+}
+
+/// Rank names against each other: eligible names first, then by score,
+/// then by fewer unknowns. Sets `score.rank` on every result and returns
+/// indices into `results` from best to worst.
+pub fn rank(results: &mut [CheckResult]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..results.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (&results[a], &results[b]);
+        b.score
+            .eligible
+            .cmp(&a.score.eligible)
+            .then(b.score.value.total_cmp(&a.score.value))
+            .then(a.summary.unknown.cmp(&b.summary.unknown))
+    });
+    for (position, &i) in order.iter().enumerate() {
+        results[i].score.rank = Some(position + 1);
+    }
+    order
+}
+
+/// The top-ranked result, if it is eligible.
+pub fn best(results: &[CheckResult]) -> Option<&CheckResult> {
+    results
+        .iter()
+        .find(|r| r.score.rank == Some(1))
+        .filter(|r| r.score.eligible)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::{self, CheckOptions};
+    use crate::types::{Availability, PackageResult, Report, ReportOptions};
+
+    fn checked(name: &str, statuses: &[Availability]) -> CheckResult {
+        let results = statuses
+            .iter()
+            .zip(["npm", "crates"])
+            .map(|(status, id)| PackageResult {
+                registry_id: id.to_string(),
+                registry_name: id.to_string(),
+                available: status.clone(),
+                browse_url: None,
+                http_status: None,
+                elapsed_ms: 1,
+                error: None,
+                details: None,
+                dormancy: None,
+            })
+            .collect();
+        checker::assemble(name, results, &CheckOptions::default(), 1)
+    }
+
+    fn report(results: Vec<CheckResult>) -> Report {
+        Report::new(
+            results,
+            Vec::new(),
+            ReportOptions::default(),
+            chrono::Utc::now(),
+        )
+    }
+
+    #[test]
+    fn report_names_the_best_ranked_candidate() {
+        use Availability::*;
+        let mut results = vec![
+            checked("ledgerly", &[Taken, Available]),
+            checked("tallyho", &[Available, Available]),
+        ];
+        let order = rank(&mut results);
+        assert_eq!(order, vec![1, 0]);
+        assert_eq!(report(results).best.as_deref(), Some("tallyho"));
+    }
+
+    #[test]
+    fn report_has_no_best_without_a_ranking() {
+        let results = vec![checked("tallyho", &[Availability::Available])];
+        assert_eq!(report(results).best, None);
+    }
+}
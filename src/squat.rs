@@ -0,0 +1,233 @@
+use crate::checker::{self, CheckOptions};
+use crate::registry::Registry;
+use crate::types::Availability;
+use crate::variants::separator_forms;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Technique {
+    Omission,
+    Transposition,
+    Doubling,
+    KeyboardAdjacent,
+    Homoglyph,
+    Separator,
+    Combosquat,
+}
+
+impl std::fmt::Display for Technique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Technique::Omission => write!(f, "omission"),
+            Technique::Transposition => write!(f, "transposition"),
+            Technique::Doubling => write!(f, "doubling"),
+            Technique::KeyboardAdjacent => write!(f, "keyboard"),
+            Technique::Homoglyph => write!(f, "homoglyph"),
+            Technique::Separator => write!(f, "separator"),
+            Technique::Combosquat => write!(f, "combosquat"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variant {
+    pub name: String,
+    pub technique: Technique,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquatFinding {
+    pub variant: String,
+    pub technique: Technique,
+    pub registry_id: String,
+    pub registry_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browse_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquatReport {
+    pub name: String,
+    pub variants: usize,
+    pub checks: usize,
+    pub taken: Vec<SquatFinding>,
+}
+
+const PREFIXES: &[&str] = &[
+    "py", "py-", "python-", "node-", "js-", "go-", "rust-", "lib",
+];
+const SUFFIXES: &[&str] = &[
+    "-js", "js", "-dev", "-cli", "-py", "-rs", "-lib", "-core", "-sdk", "-utils", "2",
+];
+
+/// Neighbouring keys on a QWERTY layout, for fat-finger substitutions.
+fn adjacent_keys(c: char) -> &'static str {
+    match c {
+        'q' => "wa",
+        'w' => "qes",
+        'e' => "wrd",
+        'r' => "etf",
+        't' => "ryg",
+        'y' => "tuh",
+        'u' => "yij",
+        'i' => "uok",
+        'o' => "ipl",
+        'p' => "ol",
+        'a' => "qsz",
+        's' => "awdz",
+        'd' => "sefx",
+        'f' => "drgc",
+        'g' => "fthv",
+        'h' => "gyjb",
+        'j' => "hukn",
+        'k' => "jilm",
+        'l' => "kop",
+        'z' => "asx",
+        'x' => "zsdc",
+        'c' => "xdfv",
+        'v' => "cfgb",
+        'b' => "vghn",
+        'n' => "bhjm",
+        'm' => "njk",
+        '1' => "2q",
+        '2' => "13w",
+        '3' => "24e",
+        '4' => "35r",
+        '5' => "46t",
+        '6' => "57y",
+        '7' => "68u",
+        '8' => "79i",
+        '9' => "80o",
+        '0' => "9p",
+        _ => "",
+    }
+}
+
+/// Character sequences that read alike in most fonts.
+const HOMOGLYPHS: &[(&str, &str)] = &[
+    ("rn", "m"),
+    ("m", "rn"),
+    ("l", "1"),
+    ("1", "l"),
+    ("l", "i"),
+    ("i", "l"),
+    ("o", "0"),
+    ("0", "o"),
+    ("vv", "w"),
+    ("w", "vv"),
+    ("cl", "d"),
+    ("d", "cl"),
+];
+
+/// Generate typo and lookalike variants of `name`, deduplicated and without
+/// `name` itself.
+pub fn variants(name: &str) -> Vec<Variant> {
+    let name = name.trim().to_lowercase();
+    let chars: Vec<char> = name.chars().collect();
+    let mut out: Vec<Variant> = Vec::new();
+    let mut push = |candidate: String, technique: Technique| {
+        if !candidate.is_empty() && candidate != name && !out.iter().any(|v| v.name == candidate) {
+            out.push(Variant {
+                name: candidate,
+                technique,
+            });
+        }
+    };
+
+    for i in 0..chars.len() {
+        let mut s = chars.clone();
+        s.remove(i);
+        push(s.into_iter().collect(), Technique::Omission);
+    }
+    for i in 0..chars.len().saturating_sub(1) {
+        let mut s = chars.clone();
+        s.swap(i, i + 1);
+        push(s.into_iter().collect(), Technique::Transposition);
+    }
+    for i in 0..chars.len() {
+        if chars[i].is_alphanumeric() {
+            let mut s = chars.clone();
+            s.insert(i, chars[i]);
+            push(s.into_iter().collect(), Technique::Doubling);
+        }
+    }
+    for i in 0..chars.len() {
+        for adjacent in adjacent_keys(chars[i]).chars() {
+            let mut s = chars.clone();
+            s[i] = adjacent;
+            push(s.into_iter().collect(), Technique::KeyboardAdjacent);
+        }
+    }
+    for &(from, to) in HOMOGLYPHS {
+        for (i, _) in name.match_indices(from) {
+            let candidate = format!("{}{}{}", &name[..i], to, &name[i + from.len()..]);
+            push(candidate, Technique::Homoglyph);
+        }
+    }
+    for form in separator_forms(&name) {
+        push(form, Technique::Separator);
+    }
+    let base = name.trim_end_matches(['-', '_', '.']);
+    for prefix in PREFIXES {
+        push(format!("{prefix}{base}"), Technique::Combosquat);
+    }
+    for suffix in SUFFIXES {
+        push(format!("{base}{suffix}"), Technique::Combosquat);
+    }
+    out
+}
+
+/// Check every variant of `name` and report the ones taken by someone else.
+pub async fn scan(
+    name: &str,
+    registries: &[&'static Registry],
+    options: &CheckOptions,
+) -> SquatReport {
+    let variants = variants(name);
+    let names: Vec<String> = variants.iter().map(|v| v.name.clone()).collect();
+    let options = CheckOptions {
+        details: true,
+        owners_only: true,
+        ..options.clone()
+    };
+    let results = checker::check_packages_with_options(&names, registries, &options).await;
+
+    let mut taken = Vec::new();
+    let mut checks = 0;
+    for result in results {
+        checks += result.results.len();
+        let Some(variant) = variants.iter().find(|v| v.name == result.name) else {
+            continue;
+        };
+        for pkg in result.results {
+            if pkg.available != Availability::Taken {
+                continue;
+            }
+            let (owners, description) = pkg
+                .details
+                .map(|d| (d.owners, d.description))
+                .unwrap_or_default();
+            taken.push(SquatFinding {
+                variant: variant.name.clone(),
+                technique: variant.technique,
+                registry_id: pkg.registry_id,
+                registry_name: pkg.registry_name,
+                browse_url: pkg.browse_url,
+                owners,
+                description,
+            });
+        }
+    }
+
+    SquatReport {
+        name: name.trim().to_string(),
+        variants: variants.len(),
+        checks,
+        taken,
+    }
+}
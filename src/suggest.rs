@@ -0,0 +1,238 @@
+use crate::checker::{self, CheckOptions};
+use crate::registry::{self, Registry};
+use crate::thesaurus::Expansion;
+use crate::types::CheckResult;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Seed,
+    Affix,
+    Truncation,
+    Portmanteau,
+    VowelDrop,
+    Doubling,
+    Synonym,
+    Translation,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Seed => write!(f, "seed"),
+            Source::Affix => write!(f, "affix"),
+            Source::Truncation => write!(f, "truncation"),
+            Source::Portmanteau => write!(f, "portmanteau"),
+            Source::VowelDrop => write!(f, "vowel drop"),
+            Source::Doubling => write!(f, "doubling"),
+            Source::Synonym => write!(f, "synonym"),
+            Source::Translation => write!(f, "translation"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candidate {
+    pub name: String,
+    pub source: Source,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub name: String,
+    pub source: Source,
+    pub result: CheckResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestReport {
+    pub seeds: Vec<String>,
+    /// Candidates generated before filtering on registry name rules.
+    pub generated: usize,
+    pub checked: usize,
+    pub suggestions: Vec<Suggestion>,
+}
+
+const PREFIXES: &[&str] = &["get", "use", "go", "open", "my"];
+const SUFFIXES: &[&str] = &["ly", "ify", "er", "io", "kit", "hq", "lab", "hub", "x"];
+
+const MIN_LEN: usize = 3;
+const MAX_LEN: usize = 24;
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// Generate name candidates from seed words, seeds first, without
+/// duplicates. Seeds are lowercased and stripped of anything but ASCII
+/// letters and digits.
+pub fn candidates(seeds: &[String]) -> Vec<Candidate> {
+    let seeds: Vec<String> = seeds
+        .iter()
+        .map(|s| {
+            s.to_lowercase()
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut out: Vec<Candidate> = Vec::new();
+    let mut push = |name: String, source: Source| {
+        let len = name.chars().count();
+        if (MIN_LEN..=MAX_LEN).contains(&len) && !out.iter().any(|c| c.name == name) {
+            out.push(Candidate { name, source });
+        }
+    };
+
+    for seed in &seeds {
+        push(seed.clone(), Source::Seed);
+    }
+    for seed in &seeds {
+        for prefix in PREFIXES {
+            push(format!("{prefix}{seed}"), Source::Affix);
+        }
+        for suffix in SUFFIXES {
+            // Drop a trailing vowel before a vowel suffix: "stake" + "ify" -> "stakify".
+            let stem = match (seed.chars().last(), suffix.chars().next()) {
+                (Some(a), Some(b)) if is_vowel(a) && is_vowel(b) => &seed[..seed.len() - 1],
+                _ => seed.as_str(),
+            };
+            push(format!("{stem}{suffix}"), Source::Affix);
+        }
+    }
+    for seed in &seeds {
+        for len in (4..seed.len()).rev() {
+            push(seed[..len].to_string(), Source::Truncation);
+        }
+    }
+    for a in &seeds {
+        for b in &seeds {
+            if a == b {
+                continue;
+            }
+            push(format!("{a}{b}"), Source::Portmanteau);
+            // Blend on a shared letter run: "ledger" + "gerbil" -> "ledgerbil".
+            if let Some(overlap) = (2..a.len().min(b.len()))
+                .rev()
+                .find(|&n| a.ends_with(&b[..n]))
+            {
+                push(format!("{}{}", a, &b[overlap..]), Source::Portmanteau);
+            }
+            let half_a = a.len().div_ceil(2);
+            let half_b = b.len() / 2;
+            push(
+                format!("{}{}", &a[..half_a], &b[half_b..]),
+                Source::Portmanteau,
+            );
+        }
+    }
+    for seed in &seeds {
+        let mut chars = seed.chars();
+        let first = chars.next().unwrap_or_default();
+        let rest: String = chars.collect();
+        push(
+            format!("{first}{}", rest.replace(is_vowel, "")),
+            Source::VowelDrop,
+        );
+        if let Some(i) = seed.rfind(is_vowel).filter(|&i| i > 0) {
+            push(
+                format!("{}{}", &seed[..i], &seed[i + 1..]),
+                Source::VowelDrop,
+            );
+        }
+    }
+    for seed in &seeds {
+        if let Some(last) = seed.chars().last().filter(|c| !is_vowel(*c)) {
+            push(format!("{seed}{last}"), Source::Doubling);
+        }
+    }
+    out
+}
+
+/// Turn ranked thesaurus expansions into candidates, keeping the first
+/// `limit` that aren't already in `existing`.
+pub fn expansion_candidates(
+    expansions: &[Expansion],
+    existing: &[Candidate],
+    limit: usize,
+) -> Vec<Candidate> {
+    expansions
+        .iter()
+        .filter(|e| (MIN_LEN..=MAX_LEN).contains(&e.word.chars().count()))
+        .filter(|e| !existing.iter().any(|c| c.name == e.word))
+        .take(limit)
+        .map(|e| Candidate {
+            name: e.word.clone(),
+            source: if e.via == "synonym" {
+                Source::Synonym
+            } else {
+                Source::Translation
+            },
+        })
+        .collect()
+}
+
+/// Generate candidates, drop the ones that break a selected registry's name
+/// rules, check the rest, and return the `top` by score (names free on every
+/// required registry first, shorter names first on ties).
+pub async fn suggest(
+    seeds: &[String],
+    registries: &[&'static Registry],
+    top: usize,
+    options: &CheckOptions,
+) -> SuggestReport {
+    rank(candidates(seeds), seeds, registries, top, options).await
+}
+
+/// Check pre-generated candidates and rank them; `suggest` without the
+/// generation step.
+pub async fn rank(
+    generated: Vec<Candidate>,
+    seeds: &[String],
+    registries: &[&'static Registry],
+    top: usize,
+    options: &CheckOptions,
+) -> SuggestReport {
+    let total = generated.len();
+    let valid: Vec<Candidate> = generated
+        .into_iter()
+        .filter(|c| {
+            registries
+                .iter()
+                .all(|r| registry::is_valid_name(r, &c.name))
+        })
+        .collect();
+    let names: Vec<String> = valid.iter().map(|c| c.name.clone()).collect();
+    let results = checker::check_packages_with_options(&names, registries, options).await;
+
+    let mut suggestions: Vec<Suggestion> = results
+        .into_iter()
+        .filter_map(|result| {
+            let candidate = valid.iter().find(|c| c.name == result.name)?;
+            Some(Suggestion {
+                name: candidate.name.clone(),
+                source: candidate.source,
+                result,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.result
+            .score
+            .eligible
+            .cmp(&a.result.score.eligible)
+            .then(b.result.score.value.total_cmp(&a.result.score.value))
+            .then(a.name.len().cmp(&b.name.len()))
+    });
+    suggestions.truncate(top);
+
+    SuggestReport {
+        seeds: seeds.to_vec(),
+        generated: total,
+        checked: names.len(),
+        suggestions,
+    }
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+/// WordNet-style synsets and English-to-X word lists, bundled so
+/// `suggest --synonyms` works offline.
+const THESAURUS: &str = include_str!("../data/thesaurus.txt");
+
+const TRANSLATIONS: &[(&str, &str)] = &[
+    ("de", include_str!("../data/translations/de.tsv")),
+    ("es", include_str!("../data/translations/es.tsv")),
+    ("fr", include_str!("../data/translations/fr.tsv")),
+    ("it", include_str!("../data/translations/it.tsv")),
+    ("la", include_str!("../data/translations/la.tsv")),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Expansion {
+    pub word: String,
+    /// The seed it was expanded from.
+    pub seed: String,
+    /// `synonym`, or the language code for a translation.
+    pub via: String,
+    /// 0.0-1.0, see `pronounceability`.
+    pub pronounceability: f64,
+}
+
+/// Language codes with a bundled word list.
+pub fn languages() -> Vec<&'static str> {
+    TRANSLATIONS.iter().map(|(code, _)| *code).collect()
+}
+
+/// Every word sharing a synset with `word`, in file order, without `word`.
+pub fn synonyms(word: &str) -> Vec<String> {
+    let word = word.trim().to_lowercase();
+    let mut out: Vec<String> = Vec::new();
+    for line in data_lines(THESAURUS) {
+        let mut tokens = line.split_whitespace();
+        let _pos = tokens.next();
+        let lemmas: Vec<&str> = tokens.collect();
+        if !lemmas.contains(&word.as_str()) {
+            continue;
+        }
+        for lemma in lemmas {
+            let lemma = lemma.replace('_', "");
+            if lemma != word && !out.contains(&lemma) {
+                out.push(lemma);
+            }
+        }
+    }
+    out
+}
+
+/// Translations of `word` into `lang`, folded to ASCII. Unknown languages
+/// yield nothing.
+pub fn translations(word: &str, lang: &str) -> Vec<String> {
+    let word = word.trim().to_lowercase();
+    let Some((_, list)) = TRANSLATIONS.iter().find(|(code, _)| *code == lang) else {
+        return Vec::new();
+    };
+    data_lines(list)
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(en, _)| *en == word)
+        .map(|(_, translated)| fold_ascii(translated))
+        .collect()
+}
+
+/// Expand seeds through synonyms (and translations of the seeds and their
+/// synonyms into `langs`), then rank the expansions: most pronounceable
+/// first, with names of 4-8 letters preferred over longer or shorter ones.
+pub fn expand(seeds: &[String], langs: &[String]) -> Vec<Expansion> {
+    let mut out: Vec<Expansion> = Vec::new();
+    let mut push = |word: String, seed: &str, via: &str| {
+        if word.is_empty() || seeds.iter().any(|s| s.eq_ignore_ascii_case(&word)) {
+            return;
+        }
+        if out.iter().any(|e| e.word == word) {
+            return;
+        }
+        out.push(Expansion {
+            pronounceability: pronounceability(&word),
+            word,
+            seed: seed.to_string(),
+            via: via.to_string(),
+        });
+    };
+
+    for seed in seeds {
+        let synonyms = synonyms(seed);
+        for synonym in &synonyms {
+            push(synonym.clone(), seed, "synonym");
+        }
+        for lang in langs {
+            for word in std::iter::once(seed).chain(&synonyms) {
+                for translated in translations(word, lang) {
+                    push(translated, seed, lang);
+                }
+            }
+        }
+    }
+
+    out.sort_by(|a, b| rank_score(b).total_cmp(&rank_score(a)));
+    out
+}
+
+fn rank_score(expansion: &Expansion) -> f64 {
+    let len = expansion.word.chars().count();
+    let length_fit = match len {
+        4..=8 => 1.0,
+        3 | 9 | 10 => 0.7,
+        _ => 0.4,
+    };
+    expansion.pronounceability * 0.7 + length_fit * 0.3
+}
+
+/// Rough 0.0-1.0 estimate of how easily a word is said aloud: penalizes
+/// long consonant or vowel runs and words without vowels, and rewards
+/// alternating consonant-vowel patterns.
+pub fn pronounceability(word: &str) -> f64 {
+    let letters: Vec<char> = word
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .collect();
+    if letters.is_empty() {
+        return 0.0;
+    }
+    let is_vowel = |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+    if !letters.iter().any(|&c| is_vowel(c)) {
+        return 0.1;
+    }
+
+    let mut penalty = 0.0;
+    let mut run = 1;
+    for pair in letters.windows(2) {
+        if is_vowel(pair[0]) == is_vowel(pair[1]) {
+            run += 1;
+            let limit = if is_vowel(pair[1]) { 2 } else { 3 };
+            if run > limit {
+                penalty += 0.2;
+            }
+        } else {
+            run = 1;
+        }
+    }
+    let switches = letters
+        .windows(2)
+        .filter(|p| is_vowel(p[0]) != is_vowel(p[1]))
+        .count();
+    let alternation = switches as f64 / (letters.len() - 1).max(1) as f64;
+    (0.5 + alternation * 0.5 - penalty).clamp(0.0, 1.0)
+}
+
+fn data_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Strip accents so translations are usable as package names.
+fn fold_ascii(word: &str) -> String {
+    let mut out = String::new();
+    for c in word.trim().to_lowercase().chars() {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => out.push('a'),
+            'ç' => out.push('c'),
+            'è' | 'é' | 'ê' | 'ë' => out.push('e'),
+            'ì' | 'í' | 'î' | 'ï' => out.push('i'),
+            'ñ' => out.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => out.push('o'),
+            'ù' | 'ú' | 'û' | 'ü' => out.push('u'),
+            'ß' => out.push_str("ss"),
+            'œ' => out.push_str("oe"),
+            'æ' => out.push_str("ae"),
+            c if c.is_ascii_alphanumeric() => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
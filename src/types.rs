@@ -1,11 +1,74 @@
-use serde::Serialize;
+use crate::policy::FailOn;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+/// Version of the `--json` document layout. Bumped only for breaking
+/// changes: removed or renamed fields, or changed meanings. New fields can
+/// appear at any time without a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The top-level `--json` document.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Report {
+    pub schema_version: u32,
+    /// Version of staked that produced the report.
+    pub staked_version: String,
+    pub generated_at: DateTime<Utc>,
+    pub options: ReportOptions,
+    /// IDs of the registries requested, in check order.
+    pub registries: Vec<String>,
+    /// The top-ranked name, when several names were ranked and it is free
+    /// on every required registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best: Option<String>,
+    pub results: Vec<CheckResult>,
+}
+
+/// The options a report was produced with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ReportOptions {
+    pub details: bool,
+    pub variants: bool,
+    /// Registries every name had to be free on.
+    #[serde(default)]
+    pub required: Vec<String>,
+    #[serde(default)]
+    pub fail_on: Vec<FailOn>,
+    /// Registry weights that differ from the defaults.
+    #[serde(default)]
+    pub weights: BTreeMap<String, f64>,
+}
+
+impl Report {
+    pub fn new(
+        results: Vec<CheckResult>,
+        registries: Vec<String>,
+        options: ReportOptions,
+        generated_at: DateTime<Utc>,
+    ) -> Report {
+        Report {
+            schema_version: SCHEMA_VERSION,
+            staked_version: env!("CARGO_PKG_VERSION").to_string(),
+            generated_at,
+            options,
+            registries,
+            best: crate::score::best(&results).map(|r| r.name.clone()),
+            results,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Availability {
     Available,
     Taken,
     Unknown,
+    /// Taken, but by one of the configured identities.
+    #[serde(rename = "owned_by_you")]
+    OwnedByYou,
 }
 
 impl std::fmt::Display for Availability {
@@ -14,42 +77,166 @@ impl std::fmt::Display for Availability {
             Availability::Available => write!(f, "AVAILABLE"),
             Availability::Taken => write!(f, "TAKEN"),
             Availability::Unknown => write!(f, "UNKNOWN"),
+            Availability::OwnedByYou => write!(f, "OWNED BY YOU"),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PackageResult {
     pub registry_id: String,
     pub registry_name: String,
     pub available: Availability,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub browse_url: Option<String>,
+    /// Status code of the availability lookup; absent if no response came.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
     pub elapsed_ms: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<TakenDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dormancy: Option<Dormancy>,
+}
+
+/// Registry metadata for a taken name, filled in when `--details` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TakenDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_published: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<Downloads>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository_archived: Option<bool>,
+    /// Every published version has been yanked or unpublished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yanked: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Downloads {
+    pub count: u64,
+    /// Window the count covers, e.g. "total", "last-week" or "365d".
+    pub period: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CheckResult {
     pub name: String,
+    /// The input name this was generated from by `--variants`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant_of: Option<String>,
     pub summary: Summary,
     pub results: Vec<PackageResult>,
+    /// Taken registries grouped by the project their metadata points at,
+    /// largest group first. Only filled in when details were fetched.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owner_groups: Vec<OwnerGroup>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dominant_owner: Option<OwnerGroup>,
+    pub score: Score,
     pub elapsed_ms: u64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Weighted availability: registries that matter more count for more, and a
+/// name taken on a required registry is not eligible however high it scores.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Score {
+    /// 0-100: the weighted share of registries that are free or ours.
+    pub value: f64,
+    /// Required registries (among those checked) that are taken or unknown.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_blocked: Vec<String>,
+    pub eligible: bool,
+    /// Position in a multi-name comparison, 1 being the best candidate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<usize>,
+}
+
+/// A set of registries whose taken packages share one upstream project.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OwnerGroup {
+    /// Normalized project identity, e.g. `github.com/foo/bar`.
+    pub project: String,
+    pub registries: Vec<String>,
+}
+
+impl std::fmt::Display for OwnerGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let n = self.registries.len();
+        write!(
+            f,
+            "taken on {} registr{} by {}",
+            n,
+            if n == 1 { "y" } else { "ies" },
+            self.project
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Summary {
     pub available: usize,
     pub taken: usize,
     pub unknown: usize,
+    pub owned_by_you: usize,
     pub total: usize,
+    /// Every registry is either available or already ours.
+    pub clear: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RegistryInfo {
     pub id: String,
     pub name: String,
     pub ecosystem: String,
     pub languages: Vec<String>,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Activity {
+    Active,
+    Dormant,
+}
+
+impl std::fmt::Display for Activity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Activity::Active => write!(f, "ACTIVE"),
+            Activity::Dormant => write!(f, "DORMANT"),
+        }
+    }
+}
+
+/// How likely a taken name is to be abandoned, derived from `TakenDetails`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Dormancy {
+    pub status: Activity,
+    /// 0-100; higher means more signs of abandonment.
+    pub score: u8,
+    pub reasons: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_policy: Option<TransferPolicy>,
+}
+
+/// The registry's process for requesting transfer of an abandoned name.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransferPolicy {
+    pub name: String,
+    pub url: String,
+}
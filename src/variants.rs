@@ -0,0 +1,98 @@
+use crate::types::CheckResult;
+
+const SEPARATORS: &[&str] = &["-", "_", "", "."];
+
+/// Ecosystem prefixes and suffixes tried by `--variants`.
+const PREFIXES: &[&str] = &["py", "node-"];
+const SUFFIXES: &[&str] = &["-rs", "-js", "-cli"];
+
+/// Rewrite every separator run in `name` as each of `-`, `_`, nothing and
+/// `.`. A name without separators has only itself.
+pub fn separator_forms(name: &str) -> Vec<String> {
+    let words: Vec<&str> = name
+        .split(['-', '_', '.'])
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.len() < 2 {
+        return vec![name.to_string()];
+    }
+    SEPARATORS.iter().map(|sep| words.join(sep)).collect()
+}
+
+/// Expand a name into its separator forms plus ecosystem affixes, starting
+/// with the name as given and without duplicates.
+pub fn expand(name: &str) -> Vec<String> {
+    let name = name.trim();
+    let mut out = vec![name.to_string()];
+    let mut push = |candidate: String| {
+        if !out.contains(&candidate) {
+            out.push(candidate);
+        }
+    };
+    for form in separator_forms(name) {
+        push(form);
+    }
+    let base = name.trim_matches(['-', '_', '.']);
+    for prefix in PREFIXES {
+        push(format!("{prefix}{base}"));
+    }
+    for suffix in SUFFIXES {
+        push(format!("{base}{suffix}"));
+    }
+    out
+}
+
+/// One result per (variant, input) pair in `origins`, with `variant_of` set.
+/// A variant two inputs share (`foo-bar` and `foo_bar` both expand to
+/// `foobar`) is checked once but appears in both inputs' groups.
+pub fn tag(checked: &[CheckResult], origins: &[(String, String)]) -> Vec<CheckResult> {
+    origins
+        .iter()
+        .filter_map(|(variant, origin)| {
+            let mut result = checked.iter().find(|r| r.name == *variant)?.clone();
+            result.variant_of = Some(origin.clone());
+            Some(result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::{self, CheckOptions};
+
+    #[test]
+    fn expand_starts_with_the_input() {
+        let variants = expand(" foo-bar ");
+        assert_eq!(variants[..4], ["foo-bar", "foo_bar", "foobar", "foo.bar"]);
+        assert!(variants.contains(&"pyfoo-bar".to_string()));
+        assert!(variants.contains(&"foo-bar-cli".to_string()));
+    }
+
+    #[test]
+    fn tag_keeps_shared_variants_in_every_group() {
+        let mut origins = Vec::new();
+        for input in ["foo-bar", "foo_bar"] {
+            for variant in expand(input) {
+                origins.push((variant, input.to_string()));
+            }
+        }
+        let options = CheckOptions::default();
+        let checked: Vec<CheckResult> = origins
+            .iter()
+            .map(|(name, _)| checker::assemble(name, Vec::new(), &options, 0))
+            .collect();
+
+        let tagged = tag(&checked, &origins);
+        assert_eq!(tagged.len(), origins.len());
+        for input in ["foo-bar", "foo_bar"] {
+            let group: Vec<&str> = tagged
+                .iter()
+                .filter(|r| r.variant_of.as_deref() == Some(input))
+                .map(|r| r.name.as_str())
+                .collect();
+            assert_eq!(group[0], input);
+            assert!(group.contains(&"foobar"));
+        }
+    }
+}
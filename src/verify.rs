@@ -0,0 +1,268 @@
+use crate::checker::{self, CheckOptions};
+use crate::config::Identities;
+use crate::registry::{self, Registry};
+use crate::types::{Availability, PackageResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A list of our packages and where each one should be published.
+///
+/// ```toml
+/// [[package]]
+/// name = "staked"
+/// registries = ["crates", "homebrew"]
+///
+/// [[package]]
+/// name = "staked-cli"   # no list: uses the registries given on the command line
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Manifest {
+    #[serde(default, rename = "package")]
+    pub packages: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    #[serde(default)]
+    pub registries: Vec<String>,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Manifest, Box<dyn std::error::Error>> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let manifest = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(manifest)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Expect {
+    /// Every listed name should be published (and ours, if identities are set)
+    Taken,
+    /// Every listed name should still be free
+    Available,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    /// Expected to be published, but the name is free.
+    Missing,
+    /// Published, but every version has been yanked or unpublished.
+    Yanked,
+    /// Published by someone other than the configured identities.
+    ForeignOwner,
+    /// Expected to be free, but the name is taken.
+    Taken,
+    /// The registry could not be checked.
+    Unknown,
+}
+
+impl std::fmt::Display for DriftKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DriftKind::Missing => write!(f, "MISSING"),
+            DriftKind::Yanked => write!(f, "YANKED"),
+            DriftKind::ForeignOwner => write!(f, "FOREIGN OWNER"),
+            DriftKind::Taken => write!(f, "TAKEN"),
+            DriftKind::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Drift {
+    pub name: String,
+    pub registry_id: String,
+    pub registry_name: String,
+    pub kind: DriftKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browse_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub expect: Expect,
+    /// Number of name/registry pairs checked.
+    pub checked: usize,
+    pub ok: usize,
+    pub drift: Vec<Drift>,
+}
+
+/// Check every manifest entry against its registries and report anything
+/// that doesn't match `expect`. Entries without their own registry list use
+/// `default_registries`. Unknown registry IDs are returned as an error.
+pub async fn verify(
+    manifest: &Manifest,
+    default_registries: &[&'static Registry],
+    expect: Expect,
+    options: &CheckOptions,
+) -> Result<VerifyReport, String> {
+    // Batch names that share a registry list into one check_packages call.
+    let mut batches: BTreeMap<Vec<&'static str>, Vec<String>> = BTreeMap::new();
+    for entry in &manifest.packages {
+        let registries = if entry.registries.is_empty() {
+            default_registries.to_vec()
+        } else {
+            let found = registry::registries_by_ids(&entry.registries);
+            if let Some(missing) = entry
+                .registries
+                .iter()
+                .find(|id| !found.iter().any(|r| r.id == id.as_str()))
+            {
+                return Err(format!("{}: unknown registry '{}'", entry.name, missing));
+            }
+            found
+        };
+        let key = registries.iter().map(|r| r.id).collect();
+        batches.entry(key).or_default().push(entry.name.clone());
+    }
+
+    let options = CheckOptions {
+        details: expect == Expect::Taken || options.details,
+        ..options.clone()
+    };
+    let identities = options.identities.clone();
+
+    let mut report = VerifyReport {
+        expect,
+        checked: 0,
+        ok: 0,
+        drift: Vec::new(),
+    };
+    for (ids, names) in batches {
+        let ids: Vec<String> = ids.into_iter().map(str::to_string).collect();
+        let registries = registry::registries_by_ids(&ids);
+        let results = checker::check_packages_with_options(&names, &registries, &options).await;
+        for result in results {
+            for pkg in result.results {
+                report.checked += 1;
+                match classify(&pkg, expect, identities.as_ref()) {
+                    None => report.ok += 1,
+                    Some(kind) => report.drift.push(drift(&result.name, pkg, kind)),
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// `ForeignOwner` needs evidence: owner names from a registry we have
+/// identities for, or a repository URL checked against GitHub identities.
+/// Without either (NuGet, Homebrew, or a registry with no identities
+/// configured) a taken name counts as ours.
+fn classify(
+    pkg: &PackageResult,
+    expect: Expect,
+    identities: Option<&Identities>,
+) -> Option<DriftKind> {
+    let yanked = pkg.details.as_ref().and_then(|d| d.yanked).unwrap_or(false);
+    let owner_known = identities.is_some_and(|ids| {
+        pkg.details.as_ref().is_some_and(|d| {
+            let ours = ids.registries.get(&pkg.registry_id);
+            (!d.owners.is_empty() && ours.is_some_and(|owners| !owners.is_empty()))
+                || (d.repository.is_some() && !ids.github.is_empty())
+        })
+    });
+    match (expect, &pkg.available) {
+        (_, Availability::Unknown) => Some(DriftKind::Unknown),
+        (Expect::Taken, Availability::Available) => Some(DriftKind::Missing),
+        (Expect::Taken, _) if yanked => Some(DriftKind::Yanked),
+        (Expect::Taken, Availability::Taken) if owner_known => Some(DriftKind::ForeignOwner),
+        (Expect::Taken, _) => None,
+        (Expect::Available, Availability::Available) => None,
+        (Expect::Available, _) => Some(DriftKind::Taken),
+    }
+}
+
+fn drift(name: &str, pkg: PackageResult, kind: DriftKind) -> Drift {
+    Drift {
+        name: name.to_string(),
+        registry_id: pkg.registry_id,
+        registry_name: pkg.registry_name,
+        kind,
+        browse_url: pkg.browse_url,
+        owners: pkg.details.map(|d| d.owners).unwrap_or_default(),
+        error: pkg.error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TakenDetails;
+    use std::collections::HashMap;
+
+    fn taken(registry_id: &str, owners: &[&str], repository: Option<&str>) -> PackageResult {
+        PackageResult {
+            registry_id: registry_id.to_string(),
+            registry_name: registry_id.to_string(),
+            available: Availability::Taken,
+            browse_url: None,
+            http_status: Some(200),
+            elapsed_ms: 1,
+            error: None,
+            details: Some(TakenDetails {
+                owners: owners.iter().map(|o| o.to_string()).collect(),
+                repository: repository.map(str::to_string),
+                ..TakenDetails::default()
+            }),
+            dormancy: None,
+        }
+    }
+
+    fn identities(github: &[&str], registries: &[(&str, &[&str])]) -> Identities {
+        Identities {
+            github: github.iter().map(|g| g.to_string()).collect(),
+            registries: registries
+                .iter()
+                .map(|(id, owners)| {
+                    let owners = owners.iter().map(|o| o.to_string()).collect();
+                    (id.to_string(), owners)
+                })
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn owners_only_count_on_registries_with_identities() {
+        let ids = identities(&[], &[("npm", &["alice"])]);
+        let crates = taken("crates", &["someone"], None);
+        assert_eq!(classify(&crates, Expect::Taken, Some(&ids)), None);
+        let npm = taken("npm", &["mallory"], None);
+        assert_eq!(
+            classify(&npm, Expect::Taken, Some(&ids)),
+            Some(DriftKind::ForeignOwner)
+        );
+    }
+
+    #[test]
+    fn repository_counts_only_with_github_identities() {
+        let pkg = taken("nuget", &[], Some("https://github.com/mallory/staked"));
+        let registries_only = identities(&[], &[("npm", &["alice"])]);
+        assert_eq!(classify(&pkg, Expect::Taken, Some(&registries_only)), None);
+        let github = identities(&["acme"], &[]);
+        assert_eq!(
+            classify(&pkg, Expect::Taken, Some(&github)),
+            Some(DriftKind::ForeignOwner)
+        );
+    }
+
+    #[test]
+    fn no_identities_means_no_foreign_owner() {
+        let pkg = taken("npm", &["mallory"], None);
+        assert_eq!(classify(&pkg, Expect::Taken, None), None);
+        assert_eq!(
+            classify(&pkg, Expect::Available, None),
+            Some(DriftKind::Taken)
+        );
+    }
+}